@@ -0,0 +1,370 @@
+use color::ColorPairId;
+use error::*;
+use general::*;
+use style::Style;
+use styled_text::StyledText;
+
+/// The curses colors of the 8 basic ANSI colors, in ANSI order.
+const BASIC_COLORS: [i16; 8] = [
+    COLOR_BLACK,
+    COLOR_RED,
+    COLOR_GREEN,
+    COLOR_YELLOW,
+    COLOR_BLUE,
+    COLOR_MAGENTA,
+    COLOR_CYAN,
+    COLOR_WHITE,
+];
+
+/// The RGB values xterm uses for the 16 basic ANSI colors.
+const BASIC_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The levels of each channel in the 6x6x6 color cube of the 256 color
+/// palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The colors text with ANSI escape sequences is drawn with.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Palette {
+    /// The number of colors the terminal supports.
+    pub colors: i32,
+    /// Whether color `-1` is the terminal's default color.
+    pub default_colors: bool,
+}
+
+impl Palette {
+    /// Get the curses color closest to the color with 256 color palette
+    /// index `index`.
+    fn indexed(self, index: u8) -> i16 {
+        if i32::from(index) < self.colors {
+            curses_color(index)
+        } else {
+            let (red, green, blue) = rgb(index);
+            self.nearest(red, green, blue)
+        }
+    }
+
+    /// Get the curses color closest to an RGB color.
+    fn nearest(self, red: u8, green: u8, blue: u8) -> i16 {
+        let available = self.colors.clamp(1, 256);
+        let distance = |index: &i32| {
+            let (r, g, b) = rgb(*index as u8);
+            let square = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+            square(r, red) + square(g, green) + square(b, blue)
+        };
+        let index = (0..available).min_by_key(distance).unwrap_or(0);
+        curses_color(index as u8)
+    }
+
+    fn default_foreground(self) -> i16 {
+        if self.default_colors {
+            -1
+        } else {
+            COLOR_WHITE
+        }
+    }
+
+    fn default_background(self) -> i16 {
+        if self.default_colors {
+            -1
+        } else {
+            COLOR_BLACK
+        }
+    }
+}
+
+/// Convert a 256 color palette index to the curses color number.  They
+/// only differ in the order of the basic colors on some platforms.
+fn curses_color(index: u8) -> i16 {
+    if index < 16 {
+        BASIC_COLORS[usize::from(index % 8)] + 8 * i16::from(index / 8)
+    } else {
+        i16::from(index)
+    }
+}
+
+/// Get the RGB value of a 256 color palette index.
+fn rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC_RGB[usize::from(index)],
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[usize::from(cube / 36)],
+                CUBE_LEVELS[usize::from(cube / 6 % 6)],
+                CUBE_LEVELS[usize::from(cube % 6)],
+            )
+        }
+        _ => {
+            let gray = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// The style set by SGR sequences so far.
+#[derive(Clone, Copy, Default)]
+struct State {
+    attributes: Chtype,
+    foreground: Option<i16>,
+    background: Option<i16>,
+}
+
+impl State {
+    /// Apply the parameters of an SGR sequence.
+    fn apply(&mut self, parameters: &str, palette: Palette) {
+        let mut parameters = parameters
+            .split([';', ':'])
+            .map(|parameter| parameter.parse::<u32>().unwrap_or(0));
+        while let Some(parameter) = parameters.next() {
+            match parameter {
+                0 => *self = State::default(),
+                1 => self.attributes |= A_BOLD,
+                2 => self.attributes |= A_DIM,
+                3 => self.attributes |= A_ITALIC,
+                4 => self.attributes |= A_UNDERLINE,
+                5 | 6 => self.attributes |= A_BLINK,
+                7 => self.attributes |= A_REVERSE,
+                22 => self.attributes &= !(A_BOLD | A_DIM),
+                23 => self.attributes &= !A_ITALIC,
+                24 => self.attributes &= !A_UNDERLINE,
+                25 => self.attributes &= !A_BLINK,
+                27 => self.attributes &= !A_REVERSE,
+                30..=37 => self.foreground = Some(palette.indexed(parameter as u8 - 30)),
+                38 => self.foreground = extended_color(&mut parameters, palette),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(palette.indexed(parameter as u8 - 40)),
+                48 => self.background = extended_color(&mut parameters, palette),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(palette.indexed(parameter as u8 - 90 + 8)),
+                100..=107 => self.background = Some(palette.indexed(parameter as u8 - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+
+    fn style(
+        self,
+        palette: Palette,
+        allocate: &mut dyn FnMut(i16, i16) -> Result<ColorPairId, Error>,
+    ) -> Result<Style, Error> {
+        let style = Style::new().with_attributes(self.attributes);
+        if self.foreground.is_none() && self.background.is_none() {
+            return Ok(style);
+        }
+        let pair = allocate(
+            self.foreground
+                .unwrap_or_else(|| palette.default_foreground()),
+            self.background
+                .unwrap_or_else(|| palette.default_background()),
+        );
+        match pair {
+            Ok(pair) => Ok(style.with_color_pair(pair)),
+            // Draw without colors rather than not at all.
+            Err(ref e) if e.kind() == ErrorKind::Exhausted => Ok(style),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Parse the color of a `38` or `48` parameter: either `5;index` or
+/// `2;red;green;blue`.
+fn extended_color<I: Iterator<Item = u32>>(parameters: &mut I, palette: Palette) -> Option<i16> {
+    let channel = |value: Option<u32>| value.unwrap_or(0).min(255) as u8;
+    match parameters.next() {
+        Some(5) => Some(palette.indexed(channel(parameters.next()))),
+        Some(2) => {
+            let red = channel(parameters.next());
+            let green = channel(parameters.next());
+            let blue = channel(parameters.next());
+            Some(palette.nearest(red, green, blue))
+        }
+        _ => None,
+    }
+}
+
+/// Split the escape sequence at the start of `text`, which follows an
+/// `ESC`, from the rest of `text`.  Returns the parameters of the
+/// sequence if it is an SGR sequence.
+fn split_sequence(text: &str) -> (Option<&str>, &str) {
+    match text.chars().next() {
+        // Control Sequence Introducer.
+        Some('[') => {
+            let body = &text[1..];
+            match body.find(|c| ('@'..='~').contains(&c)) {
+                Some(end) if body[end..].starts_with('m') => (Some(&body[..end]), &body[end + 1..]),
+                Some(end) => (None, &body[end + 1..]),
+                None => (None, ""),
+            }
+        }
+        // Strings terminated by `BEL` or `ESC \`.
+        Some(']') | Some('P') | Some('X') | Some('^') | Some('_') => {
+            let body = &text[1..];
+            match body.find(['\x07', '\x1b']) {
+                Some(end) if body[end..].starts_with("\x1b\\") => (None, &body[end + 2..]),
+                Some(end) if body[end..].starts_with('\x07') => (None, &body[end + 1..]),
+                Some(end) => (None, &body[end..]),
+                None => (None, ""),
+            }
+        }
+        // Other sequences are intermediate bytes followed by a final
+        // byte, such as `ESC ( B`.
+        Some(_) => {
+            let rest = text.trim_start_matches(|c| (' '..='/').contains(&c));
+            let mut chars = rest.chars();
+            chars.next();
+            (None, chars.as_str())
+        }
+        None => (None, ""),
+    }
+}
+
+/// Parse text with ANSI escape sequences into a `StyledText`.
+pub(crate) fn parse(
+    text: &str,
+    palette: Palette,
+    allocate: &mut dyn FnMut(i16, i16) -> Result<ColorPairId, Error>,
+) -> Result<StyledText, Error> {
+    let mut styled = StyledText::new();
+    let mut state = State::default();
+    let mut style = Style::new();
+    let mut rest = text;
+    while let Some(escape) = rest.find('\x1b') {
+        styled.push(&rest[..escape], style);
+        let (parameters, after) = split_sequence(&rest[escape + 1..]);
+        rest = after;
+        if let Some(parameters) = parameters {
+            state.apply(parameters, palette);
+            style = state.style(palette, allocate)?;
+        }
+    }
+    styled.push(rest, style);
+    Ok(styled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use styled_text::Span;
+
+    const PALETTE: Palette = Palette {
+        colors: 8,
+        default_colors: false,
+    };
+
+    /// Number pairs by their colors.  This is unique as long as the
+    /// background is a basic color or `-1`.
+    fn pair(foreground: i16, background: i16) -> ColorPairId {
+        ColorPairId::new((foreground + 1) * 20 + background + 1)
+    }
+
+    fn parse_with(text: &str, palette: Palette) -> Vec<Span> {
+        parse(text, palette, &mut |foreground, background| {
+            Ok(pair(foreground, background))
+        })
+        .unwrap()
+        .spans()
+        .to_vec()
+    }
+
+    #[test]
+    fn attributes() {
+        assert_eq!(
+            parse_with(
+                "\x1b[1;4mbold\x1b[22m under\x1b[0m plain\x1b[7;3mrev\x1b[m",
+                PALETTE
+            ),
+            [
+                Span::new("bold", A_BOLD | A_UNDERLINE),
+                Span::new(" under", A_UNDERLINE),
+                Span::new(" plain", Style::new()),
+                Span::new("rev", A_REVERSE | A_ITALIC),
+            ]
+        );
+    }
+
+    #[test]
+    fn basic_colors() {
+        assert_eq!(
+            parse_with("\x1b[31merror\x1b[44m!\x1b[39m?\x1b[49m.", PALETTE),
+            [
+                Span::new("error", pair(COLOR_RED, COLOR_BLACK)),
+                Span::new("!", pair(COLOR_RED, COLOR_BLUE)),
+                Span::new("?", pair(COLOR_WHITE, COLOR_BLUE)),
+                Span::new(".", Style::new()),
+            ]
+        );
+        let defaults = Palette {
+            colors: 16,
+            default_colors: true,
+        };
+        assert_eq!(
+            parse_with("\x1b[91mx", defaults),
+            [Span::new("x", pair(COLOR_RED + 8, -1))]
+        );
+        // Without bright colors, the basic color is used.
+        assert_eq!(
+            parse_with("\x1b[91mx", PALETTE),
+            [Span::new("x", pair(COLOR_RED, COLOR_BLACK))]
+        );
+    }
+
+    #[test]
+    fn extended_colors() {
+        let palette = Palette {
+            colors: 256,
+            default_colors: false,
+        };
+        assert_eq!(
+            parse_with("\x1b[38;5;208ma\x1b[38;2;0;0;0;48;2;255;255;255mb", palette),
+            [
+                Span::new("a", pair(208, COLOR_BLACK)),
+                Span::new("b", pair(COLOR_BLACK, COLOR_WHITE + 8)),
+            ]
+        );
+        assert_eq!(
+            parse_with("\x1b[38;5;208ma\x1b[38;2;0;0;200mb", PALETTE),
+            [
+                Span::new("a", pair(COLOR_YELLOW, COLOR_BLACK)),
+                Span::new("b", pair(COLOR_BLUE, COLOR_BLACK)),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_sequences() {
+        assert_eq!(
+            parse_with(
+                "\x1b[2Ja\x1b]0;title\x07b\x1b]8;;url\x1b\\c\x1b(Bd\x1b[?25l",
+                PALETTE
+            ),
+            [Span::new("abcd", Style::new())]
+        );
+    }
+
+    #[test]
+    fn exhausted_pairs() {
+        let text = parse("\x1b[1;31mx", PALETTE, &mut |_, _| {
+            Err(Error::new(ErrorKind::Exhausted, "init_pair"))
+        })
+        .unwrap();
+        assert_eq!(text.spans(), [Span::new("x", A_BOLD)]);
+    }
+}
@@ -0,0 +1,210 @@
+use cell::Cell;
+use color::ColorPairId;
+use error::*;
+use general::*;
+use key::KeyEvent;
+use point::*;
+use window::Window;
+
+/// The drawing and input operations of a [`Window`].
+///
+/// Code that draws through a `Backend` rather than a [`Window`] can be
+/// tested without a terminal by drawing on a [`VirtualScreen`] instead.
+///
+/// The methods behave like the [`Window`] methods of the same name.
+/// Unlike those methods they don't take generic arguments, so that
+/// `&mut dyn Backend` can be used.
+///
+/// [`Window`]: struct.Window.html
+/// [`VirtualScreen`]: struct.VirtualScreen.html
+pub trait Backend {
+    /// See [`Window::size`](struct.Window.html#method.size).
+    fn size(&self) -> Dimension;
+    /// See [`Window::point`](struct.Window.html#method.point).
+    fn point(&self) -> Point;
+    /// See [`Window::move_to`](struct.Window.html#method.move_to).
+    fn move_to(&mut self, p: Point) -> Result<(), Error>;
+
+    /// See [`Window::put_char`](struct.Window.html#method.put_char).
+    fn put_char(&mut self, ch: Chtype) -> Result<(), Error>;
+    /// See [`Window::put_str`](struct.Window.html#method.put_str).
+    fn put_str(&mut self, string: &str) -> Result<(), Error>;
+    /// See [`Window::move_put_char`](struct.Window.html#method.move_put_char).
+    fn move_put_char(&mut self, p: Point, ch: Chtype) -> Result<(), Error> {
+        self.move_to(p)?;
+        self.put_char(ch)
+    }
+    /// See [`Window::move_put_str`](struct.Window.html#method.move_put_str).
+    fn move_put_str(&mut self, p: Point, string: &str) -> Result<(), Error> {
+        self.move_to(p)?;
+        self.put_str(string)
+    }
+
+    /// See [`Window::attributes`](struct.Window.html#method.attributes).
+    fn attributes(&self) -> (Chtype, ColorPairId);
+    /// See [`Window::set_attributes`](struct.Window.html#method.set_attributes).
+    fn set_attributes(&mut self, attributes: Chtype) -> Result<(), Error>;
+    /// See [`Window::turn_on_attributes`](struct.Window.html#method.turn_on_attributes).
+    fn turn_on_attributes(&mut self, attributes: Chtype) -> Result<(), Error>;
+    /// See [`Window::turn_off_attributes`](struct.Window.html#method.turn_off_attributes).
+    fn turn_off_attributes(&mut self, attributes: Chtype) -> Result<(), Error>;
+    /// See [`Window::set_color`](struct.Window.html#method.set_color).
+    fn set_color(&mut self, color_pair: ColorPairId) -> Result<(), Error>;
+
+    /// See [`Window::clear`](struct.Window.html#method.clear).
+    fn clear(&mut self) -> Result<(), Error>;
+    /// See [`Window::erase`](struct.Window.html#method.erase).
+    fn erase(&mut self) -> Result<(), Error>;
+    /// See [`Window::clear_to_bottom`](struct.Window.html#method.clear_to_bottom).
+    fn clear_to_bottom(&mut self) -> Result<(), Error>;
+    /// See [`Window::clear_to_end_of_line`](struct.Window.html#method.clear_to_end_of_line).
+    fn clear_to_end_of_line(&mut self) -> Result<(), Error>;
+    /// See [`Window::set_scroll_enabled`](struct.Window.html#method.set_scroll_enabled).
+    fn set_scroll_enabled(&mut self, scroll: bool) -> Result<(), Error>;
+
+    /// See [`Window::draw_border`](struct.Window.html#method.draw_border).
+    #[allow(clippy::too_many_arguments)]
+    fn draw_border(
+        &mut self,
+        left_side: Chtype,
+        right_side: Chtype,
+        top_side: Chtype,
+        bottom_side: Chtype,
+        top_left_corner: Chtype,
+        top_right_corner: Chtype,
+        bottom_left_corner: Chtype,
+        bottom_right_corner: Chtype,
+    ) -> Result<(), Error>;
+    /// See [`Window::draw_box`](struct.Window.html#method.draw_box).
+    fn draw_box(&mut self, vertical: Chtype, horizontal: Chtype) -> Result<(), Error> {
+        self.draw_border(vertical, vertical, horizontal, horizontal, 0, 0, 0, 0)
+    }
+
+    /// See [`Window::cell_at`](struct.Window.html#method.cell_at).
+    fn cell_at(&self, p: Point) -> Result<Cell, Error>;
+    /// See [`Window::line_text`](struct.Window.html#method.line_text).
+    fn line_text(&self, row: i32) -> Result<String, Error> {
+        (0..self.size().columns)
+            .map(|x| self.cell_at(Point { y: row, x }).map(|cell| cell.character))
+            .collect()
+    }
+    /// See [`Window::text`](struct.Window.html#method.text).
+    fn text(&self) -> Vec<String> {
+        (0..self.size().rows)
+            .filter_map(|row| self.line_text(row).ok())
+            .collect()
+    }
+
+    /// See [`Window::read_char`](struct.Window.html#method.read_char).
+    fn read_char(&mut self) -> Option<Input>;
+    /// See [`Window::read_key`](struct.Window.html#method.read_key).
+    fn read_key(&mut self) -> Option<KeyEvent>;
+
+    /// See [`Window::refresh`](struct.Window.html#method.refresh).
+    fn refresh(&mut self) -> Result<(), Error>;
+}
+
+impl Backend for Window {
+    fn size(&self) -> Dimension {
+        Window::size(self)
+    }
+    fn point(&self) -> Point {
+        Window::point(self)
+    }
+    fn move_to(&mut self, p: Point) -> Result<(), Error> {
+        Window::move_to(self, p)
+    }
+
+    fn put_char(&mut self, ch: Chtype) -> Result<(), Error> {
+        Window::put_char(self, ch)
+    }
+    fn put_str(&mut self, string: &str) -> Result<(), Error> {
+        Window::put_str(self, string)
+    }
+    fn move_put_char(&mut self, p: Point, ch: Chtype) -> Result<(), Error> {
+        Window::move_put_char(self, p, ch)
+    }
+    fn move_put_str(&mut self, p: Point, string: &str) -> Result<(), Error> {
+        Window::move_put_str(self, p, string)
+    }
+
+    fn attributes(&self) -> (Chtype, ColorPairId) {
+        Window::attributes(self)
+    }
+    fn set_attributes(&mut self, attributes: Chtype) -> Result<(), Error> {
+        Window::set_attributes(self, attributes)
+    }
+    fn turn_on_attributes(&mut self, attributes: Chtype) -> Result<(), Error> {
+        Window::turn_on_attributes(self, attributes)
+    }
+    fn turn_off_attributes(&mut self, attributes: Chtype) -> Result<(), Error> {
+        Window::turn_off_attributes(self, attributes)
+    }
+    fn set_color(&mut self, color_pair: ColorPairId) -> Result<(), Error> {
+        Window::set_color(self, color_pair)
+    }
+
+    fn clear(&mut self) -> Result<(), Error> {
+        Window::clear(self)
+    }
+    fn erase(&mut self) -> Result<(), Error> {
+        Window::erase(self)
+    }
+    fn clear_to_bottom(&mut self) -> Result<(), Error> {
+        Window::clear_to_bottom(self)
+    }
+    fn clear_to_end_of_line(&mut self) -> Result<(), Error> {
+        Window::clear_to_end_of_line(self)
+    }
+    fn set_scroll_enabled(&mut self, scroll: bool) -> Result<(), Error> {
+        Window::set_scroll_enabled(self, scroll)
+    }
+
+    fn draw_border(
+        &mut self,
+        left_side: Chtype,
+        right_side: Chtype,
+        top_side: Chtype,
+        bottom_side: Chtype,
+        top_left_corner: Chtype,
+        top_right_corner: Chtype,
+        bottom_left_corner: Chtype,
+        bottom_right_corner: Chtype,
+    ) -> Result<(), Error> {
+        Window::draw_border(
+            self,
+            left_side,
+            right_side,
+            top_side,
+            bottom_side,
+            top_left_corner,
+            top_right_corner,
+            bottom_left_corner,
+            bottom_right_corner,
+        )
+    }
+    fn draw_box(&mut self, vertical: Chtype, horizontal: Chtype) -> Result<(), Error> {
+        Window::draw_box(self, vertical, horizontal)
+    }
+
+    fn cell_at(&self, p: Point) -> Result<Cell, Error> {
+        Window::cell_at(self, p)
+    }
+    fn line_text(&self, row: i32) -> Result<String, Error> {
+        Window::line_text(self, row)
+    }
+    fn text(&self) -> Vec<String> {
+        Window::text(self)
+    }
+
+    fn read_char(&mut self) -> Option<Input> {
+        Window::read_char(self)
+    }
+    fn read_key(&mut self) -> Option<KeyEvent> {
+        Window::read_key(self)
+    }
+
+    fn refresh(&mut self) -> Result<(), Error> {
+        Window::refresh(self)
+    }
+}
@@ -0,0 +1,263 @@
+use error::*;
+use general::*;
+use sys;
+use window::Window;
+use wrap::{self, Alignment};
+
+/// The characters of a border.
+///
+/// See [`BorderStyle::Custom`].
+///
+/// [`BorderStyle::Custom`]: enum.BorderStyle.html#variant.Custom
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BorderChars {
+    pub left_side: char,
+    pub right_side: char,
+    pub top_side: char,
+    pub bottom_side: char,
+    pub top_left_corner: char,
+    pub top_right_corner: char,
+    pub bottom_left_corner: char,
+    pub bottom_right_corner: char,
+}
+
+impl BorderChars {
+    /// Create `BorderChars` with the same character for both vertical
+    /// sides and for both horizontal sides.
+    pub fn new(
+        vertical: char,
+        horizontal: char,
+        top_left_corner: char,
+        top_right_corner: char,
+        bottom_left_corner: char,
+        bottom_right_corner: char,
+    ) -> Self {
+        BorderChars {
+            left_side: vertical,
+            right_side: vertical,
+            top_side: horizontal,
+            bottom_side: horizontal,
+            top_left_corner,
+            top_right_corner,
+            bottom_left_corner,
+            bottom_right_corner,
+        }
+    }
+
+    /// Get the characters in the order of `border`.
+    fn to_array(self) -> [char; 8] {
+        [
+            self.left_side,
+            self.right_side,
+            self.top_side,
+            self.bottom_side,
+            self.top_left_corner,
+            self.top_right_corner,
+            self.bottom_left_corner,
+            self.bottom_right_corner,
+        ]
+    }
+}
+
+/// The lines a border is drawn with.
+///
+/// Every style but `Ascii` and `Acs` uses Unicode box drawing
+/// characters, which are only drawn with the `wide` feature.  Without
+/// it, characters outside of ASCII are replaced by the lines of the
+/// alternate character set, as `Acs` draws.  Either way, characters
+/// outside of ASCII are replaced by `Ascii` if the locale doesn't use
+/// UTF-8.  Curses takes the locale from the environment (`LANG`,
+/// `LC_ALL`, etc.) when it starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BorderStyle {
+    /// `┌─┐│└┘`
+    #[default]
+    Single,
+    /// `╔═╗║╚╝`
+    Double,
+    /// `╭─╮│╰╯`
+    Rounded,
+    /// `┏━┓┃┗┛`
+    Heavy,
+    /// `+-+|++`
+    Ascii,
+    /// The lines of the terminal's alternate character set.  This is
+    /// what [`Window::draw_box`] draws.
+    ///
+    /// [`Window::draw_box`]: struct.Window.html#method.draw_box
+    Acs,
+    Custom(BorderChars),
+}
+
+impl BorderStyle {
+    /// Get the characters of the style, or `None` for `Acs`.
+    pub fn chars(self) -> Option<BorderChars> {
+        match self {
+            BorderStyle::Single => Some(BorderChars::new('│', '─', '┌', '┐', '└', '┘')),
+            BorderStyle::Double => Some(BorderChars::new('║', '═', '╔', '╗', '╚', '╝')),
+            BorderStyle::Rounded => Some(BorderChars::new('│', '─', '╭', '╮', '╰', '╯')),
+            BorderStyle::Heavy => Some(BorderChars::new('┃', '━', '┏', '┓', '┗', '┛')),
+            BorderStyle::Ascii => Some(BorderChars::new('|', '-', '+', '+', '+', '+')),
+            BorderStyle::Acs => None,
+            BorderStyle::Custom(chars) => Some(chars),
+        }
+    }
+}
+
+impl From<BorderChars> for BorderStyle {
+    fn from(chars: BorderChars) -> Self {
+        BorderStyle::Custom(chars)
+    }
+}
+
+/// A border with an optional title in its top edge and footer in its
+/// bottom edge.  It is drawn by [`Window::draw_styled_border`].
+///
+/// [`Window::draw_styled_border`]: struct.Window.html#method.draw_styled_border
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Border {
+    style: BorderStyle,
+    title: Option<(String, Alignment)>,
+    footer: Option<(String, Alignment)>,
+}
+
+impl Border {
+    /// Create a `Border` drawn in `style` without a title or footer.
+    pub fn new(style: BorderStyle) -> Self {
+        Border {
+            style,
+            title: None,
+            footer: None,
+        }
+    }
+
+    /// Put `title` in the top edge.  `Alignment::Justify` is treated as
+    /// `Alignment::Left`.
+    pub fn title<T: Into<String>>(self, title: T, alignment: Alignment) -> Self {
+        Border {
+            title: Some((title.into(), alignment)),
+            ..self
+        }
+    }
+    /// Put `footer` in the bottom edge.  `Alignment::Justify` is treated
+    /// as `Alignment::Left`.
+    pub fn footer<T: Into<String>>(self, footer: T, alignment: Alignment) -> Self {
+        Border {
+            footer: Some((footer.into(), alignment)),
+            ..self
+        }
+    }
+}
+
+impl From<BorderStyle> for Border {
+    fn from(style: BorderStyle) -> Self {
+        Border::new(style)
+    }
+}
+
+/// Draw `border` around the edges of `window`.
+pub(crate) fn draw(window: &mut Window, border: &Border) -> Result<(), Error> {
+    match border.style.chars() {
+        Some(chars) => draw_chars(window, fall_back(chars, sys::locale_is_utf8()))?,
+        None => {
+            let acs: Chtype = 0;
+            window.draw_border(acs, acs, acs, acs, acs, acs, acs, acs)?;
+        }
+    }
+
+    let point = window.point();
+    let rows = window.size().rows;
+    if let Some((ref title, alignment)) = border.title {
+        put_label(window, 0, title, alignment)?;
+    }
+    if let Some((ref footer, alignment)) = border.footer {
+        put_label(window, rows - 1, footer, alignment)?;
+    }
+    window.move_to(point)
+}
+
+/// Replace the characters outside of ASCII with those of
+/// `BorderStyle::Ascii` unless the locale uses UTF-8.
+fn fall_back(chars: BorderChars, utf8: bool) -> [char; 8] {
+    let mut chars = chars.to_array();
+    if !utf8 {
+        let ascii = BorderStyle::Ascii.chars().unwrap().to_array();
+        for (c, &a) in chars.iter_mut().zip(ascii.iter()) {
+            if !c.is_ascii() {
+                *c = a;
+            }
+        }
+    }
+    chars
+}
+
+#[cfg(all(unix, feature = "wide"))]
+fn draw_chars(window: &mut Window, chars: [char; 8]) -> Result<(), Error> {
+    check("wborder_set", sys::border_set(window.as_ptr(), chars))
+}
+
+#[cfg(not(all(unix, feature = "wide")))]
+fn draw_chars(window: &mut Window, chars: [char; 8]) -> Result<(), Error> {
+    // `0` draws the line of the alternate character set.  PDCurses can
+    // put any character in a `Chtype` with the `wide` feature.
+    let chtype = |c: char| {
+        if c.is_ascii() || cfg!(feature = "wide") {
+            c as Chtype
+        } else {
+            0
+        }
+    };
+    window.draw_border(
+        chtype(chars[0]),
+        chtype(chars[1]),
+        chtype(chars[2]),
+        chtype(chars[3]),
+        chtype(chars[4]),
+        chtype(chars[5]),
+        chtype(chars[6]),
+        chtype(chars[7]),
+    )
+}
+
+/// Put `label` in the edge at `row`, padded with a space on each side
+/// and cut off so that the corners stay visible.
+fn put_label(
+    window: &mut Window,
+    row: i32,
+    label: &str,
+    alignment: Alignment,
+) -> Result<(), Error> {
+    let inner = (window.size().columns - 2).max(0) as usize;
+    if label.is_empty() || inner == 0 {
+        return Ok(());
+    }
+    let padded = format!(" {} ", label);
+    let label = wrap::truncate(&padded, inner);
+    let free = inner - wrap::display_width(label);
+    let offset = match alignment {
+        Alignment::Left | Alignment::Justify => 0,
+        Alignment::Center => free / 2,
+        Alignment::Right => free,
+    };
+    window.move_put_str((row, 1 + offset as i32), label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_fallback() {
+        let single = BorderStyle::Single.chars().unwrap();
+        assert_eq!(fall_back(single, true), single.to_array());
+        assert_eq!(
+            fall_back(single, false),
+            ['|', '|', '-', '-', '+', '+', '+', '+']
+        );
+        let mixed = BorderChars::new('!', '═', '*', '*', '*', '*');
+        assert_eq!(
+            fall_back(mixed, false),
+            ['!', '!', '-', '-', '*', '*', '*', '*']
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use curses::{Curses, CursorVisibility, InputBufferingMode};
+use error::*;
+use general::*;
+use initialize::initscr;
+use std::time::Duration;
+
+/// Initializes [`Curses`] with the options most programs set at startup.
+///
+/// This is the recommended way to start curses.  Each option corresponds
+/// to a method of [`Curses`] (or of its [`Window`] or [`Color`]) and is
+/// only applied if it was set; a default `CursesBuilder` behaves like
+/// [`initscr`].
+///
+/// There is no option for the locale: starting curses always sets it
+/// from the environment (`LANG`, `LC_ALL`, etc.), and curses only
+/// writes UTF-8 if the locale uses it.
+///
+/// [`Curses`]: struct.Curses.html
+/// [`Window`]: struct.Window.html
+/// [`Color`]: struct.Color.html
+/// [`initscr`]: fn.initscr.html
+#[derive(Clone, Debug, Default)]
+pub struct CursesBuilder {
+    panic_hook: bool,
+    color: bool,
+    default_colors: bool,
+    echo_input: Option<bool>,
+    input_buffering_mode: Option<InputBufferingMode>,
+    read_interpolate_function_keys: Option<bool>,
+    translate_new_lines: Option<bool>,
+    cursor_visibility: Option<CursorVisibility>,
+    mouse_mask: Option<MouseMask>,
+    escape_delay: Option<Duration>,
+}
+
+impl CursesBuilder {
+    /// Create a `CursesBuilder` that doesn't change any options.
+    pub fn new() -> Self {
+        CursesBuilder::default()
+    }
+
+    /// Install the panic hook.  See [`Curses::install_panic_hook`].
+    ///
+    /// [`Curses::install_panic_hook`]: struct.Curses.html#method.install_panic_hook
+    pub fn panic_hook(self, panic_hook: bool) -> Self {
+        CursesBuilder { panic_hook, ..self }
+    }
+
+    /// Start the color subsystem.  See [`Curses::start_color`].
+    ///
+    /// [`Curses::start_color`]: struct.Curses.html#method.start_color
+    pub fn color(self, color: bool) -> Self {
+        CursesBuilder { color, ..self }
+    }
+
+    /// Start the color subsystem and use the default colors of the
+    /// terminal.  See [`Color::use_default_colors`].
+    ///
+    /// [`Color::use_default_colors`]: struct.Color.html#method.use_default_colors
+    pub fn default_colors(self, default_colors: bool) -> Self {
+        CursesBuilder {
+            default_colors,
+            ..self
+        }
+    }
+
+    /// See [`Curses::set_echo_input`].
+    ///
+    /// [`Curses::set_echo_input`]: struct.Curses.html#method.set_echo_input
+    pub fn echo_input(self, echo: bool) -> Self {
+        CursesBuilder {
+            echo_input: Some(echo),
+            ..self
+        }
+    }
+
+    /// See [`Curses::set_input_buffering_mode`].
+    ///
+    /// [`Curses::set_input_buffering_mode`]: struct.Curses.html#method.set_input_buffering_mode
+    pub fn input_buffering_mode(self, mode: InputBufferingMode) -> Self {
+        CursesBuilder {
+            input_buffering_mode: Some(mode),
+            ..self
+        }
+    }
+
+    /// Set [`Window::read_interpolate_function_keys`] on `stdscr`.
+    ///
+    /// [`Window::read_interpolate_function_keys`]: struct.Window.html#method.read_interpolate_function_keys
+    pub fn read_interpolate_function_keys(self, interpolate: bool) -> Self {
+        CursesBuilder {
+            read_interpolate_function_keys: Some(interpolate),
+            ..self
+        }
+    }
+
+    /// See [`Curses::set_translate_new_lines`].
+    ///
+    /// [`Curses::set_translate_new_lines`]: struct.Curses.html#method.set_translate_new_lines
+    pub fn translate_new_lines(self, translate: bool) -> Self {
+        CursesBuilder {
+            translate_new_lines: Some(translate),
+            ..self
+        }
+    }
+
+    /// See [`Curses::set_cursor_visibility`].
+    ///
+    /// [`Curses::set_cursor_visibility`]: struct.Curses.html#method.set_cursor_visibility
+    pub fn cursor_visibility(self, visibility: CursorVisibility) -> Self {
+        CursesBuilder {
+            cursor_visibility: Some(visibility),
+            ..self
+        }
+    }
+
+    /// See [`Curses::set_mouse_mask`].
+    ///
+    /// [`Curses::set_mouse_mask`]: struct.Curses.html#method.set_mouse_mask
+    pub fn mouse_mask(self, mask: MouseMask) -> Self {
+        CursesBuilder {
+            mouse_mask: Some(mask),
+            ..self
+        }
+    }
+
+    /// See [`Curses::set_escape_delay`].
+    ///
+    /// [`Curses::set_escape_delay`]: struct.Curses.html#method.set_escape_delay
+    pub fn escape_delay(self, delay: Duration) -> Self {
+        CursesBuilder {
+            escape_delay: Some(delay),
+            ..self
+        }
+    }
+
+    /// Initialize curses and apply the options.
+    ///
+    /// If any option fails to apply, curses is ended again before the
+    /// error is returned, leaving the terminal as it was.
+    ///
+    /// This fails with [`ErrorKind::AlreadyInitialized`] if curses is
+    /// already running.
+    ///
+    /// [`ErrorKind::AlreadyInitialized`]: enum.ErrorKind.html#variant.AlreadyInitialized
+    pub fn build(self) -> Result<Curses, Error> {
+        let mut curses = initscr()?;
+        // Dropping `curses` ends it if an option fails.
+        self.apply(&mut curses)?;
+        Ok(curses)
+    }
+
+    fn apply(self, curses: &mut Curses) -> Result<(), Error> {
+        if self.panic_hook {
+            curses.install_panic_hook();
+        }
+        if self.color || self.default_colors {
+            let color = curses.start_color()?;
+            if self.default_colors {
+                color.use_default_colors()?;
+            }
+        }
+        if let Some(echo) = self.echo_input {
+            curses.set_echo_input(echo)?;
+        }
+        if let Some(mode) = self.input_buffering_mode {
+            curses.set_input_buffering_mode(mode)?;
+        }
+        if let Some(interpolate) = self.read_interpolate_function_keys {
+            curses
+                .window_mut()
+                .read_interpolate_function_keys(interpolate)?;
+        }
+        if let Some(translate) = self.translate_new_lines {
+            curses.set_translate_new_lines(translate)?;
+        }
+        if let Some(visibility) = self.cursor_visibility {
+            curses.set_cursor_visibility(visibility)?;
+        }
+        if let Some(mask) = self.mouse_mask {
+            curses.set_mouse_mask(mask, None)?;
+        }
+        if let Some(delay) = self.escape_delay {
+            curses.set_escape_delay(delay)?;
+        }
+        Ok(())
+    }
+}
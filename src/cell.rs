@@ -0,0 +1,121 @@
+use color::ColorPairId;
+use general::*;
+use point::*;
+use window::Window;
+
+/// The contents of a single position in a [`Window`].
+///
+/// A `Cell` is the decoded form of the `Chtype` curses stores for each
+/// position.  To read cells, see [`Window::cell_at`] and
+/// [`Window::cells`].
+///
+/// Characters drawn with `A_ALTCHARSET` (such as borders) are stored as
+/// the character that selects them in the alternate character set.
+/// Without the `wide` feature, characters that don't fit in a `Chtype`
+/// are not stored by curses and can't be read back.  With it, a
+/// character that takes up two columns is read from both of its
+/// positions.
+///
+/// [`Window`]: struct.Window.html
+/// [`Window::cell_at`]: struct.Window.html#method.cell_at
+/// [`Window::cells`]: struct.Window.html#method.cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub character: char,
+    /// The attributes, such as `A_BOLD`, excluding the color pair.
+    pub attributes: Chtype,
+    pub color_pair: ColorPairId,
+}
+
+impl From<Chtype> for Cell {
+    // `Chtype` is wider than `u32` on some platforms.
+    #[allow(clippy::unnecessary_cast)]
+    fn from(ch: Chtype) -> Self {
+        // `A_CHARTEXT` is wrong in `pancurses` for ncurses, so the text is
+        // taken to be everything that isn't an attribute.
+        let character = std::char::from_u32((ch & !A_ATTRIBUTES) as u32)
+            .unwrap_or(std::char::REPLACEMENT_CHARACTER);
+        let pair = (ch & A_COLOR) >> A_COLOR.trailing_zeros();
+        Cell {
+            character,
+            attributes: ch & A_ATTRIBUTES & !A_COLOR,
+            color_pair: ColorPairId::new(pair as i16),
+        }
+    }
+}
+
+impl From<Cell> for Chtype {
+    fn from(cell: Cell) -> Chtype {
+        cell.character as Chtype | cell.attributes | Chtype::from(cell.color_pair)
+    }
+}
+
+/// An iterator over the [`Cell`]s in a rectangle of a [`Window`], row
+/// by row.
+///
+/// To create a `Cells`, see [`Window::cells`].
+///
+/// [`Cell`]: struct.Cell.html
+/// [`Window`]: struct.Window.html
+/// [`Window::cells`]: struct.Window.html#method.cells
+pub struct Cells<'a> {
+    window: &'a Window,
+    start: Point,
+    size: Dimension,
+    next: Point,
+}
+
+impl<'a> Cells<'a> {
+    pub(crate) fn new(window: &'a Window, start: Point, size: Dimension) -> Self {
+        Cells {
+            window,
+            start,
+            size,
+            next: Point { y: 0, x: 0 },
+        }
+    }
+}
+
+impl<'a> Iterator for Cells<'a> {
+    /// The point of the `Cell` in the `Window` and the `Cell`.
+    type Item = (Point, Cell);
+
+    fn next(&mut self) -> Option<(Point, Cell)> {
+        if self.size.columns <= 0 || self.next.y >= self.size.rows {
+            return None;
+        }
+        let p = Point {
+            y: self.start.y + self.next.y,
+            x: self.start.x + self.next.x,
+        };
+        self.next.x += 1;
+        if self.next.x == self.size.columns {
+            self.next.x = 0;
+            self.next.y += 1;
+        }
+        Some((p, self.window.read_cell(p)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_round_trips_through_chtype() {
+        let cell = Cell {
+            character: 'x',
+            attributes: A_BOLD | A_UNDERLINE,
+            color_pair: ColorPairId::new(3),
+        };
+        assert_eq!(Cell::from(Chtype::from(cell)), cell);
+    }
+
+    #[test]
+    fn cell_from_plain_character() {
+        let cell = Cell::from('a' as Chtype);
+        assert_eq!(cell.character, 'a');
+        assert_eq!(cell.attributes, 0);
+        assert_eq!(cell.color_pair, ColorPairId::default());
+    }
+}
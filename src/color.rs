@@ -1,5 +1,7 @@
+use ansi::Palette;
+use error::*;
 use general::*;
-use std::marker::PhantomData;
+use std::collections::HashMap;
 
 /// A color value represented as RGB
 pub struct ColorContent {
@@ -22,31 +24,268 @@ impl From<ColorContent> for (i16, i16, i16) {
     }
 }
 
-/// Color subsystem.  It can be accessed via [`Curses::color`].
+/// A handle to a color pair.
 ///
-/// [`Curses::color`]: struct.Curses.html#method.color
+/// `ColorPairId`s are handed out by [`ColorPairs::allocate`] and
+/// [`Color::set_color_pair`].  The default `ColorPairId` is pair 0,
+/// which always uses the terminal's default colors.
+///
+/// As only the [`Color`] subsystem hands out other pairs, `Window`
+/// methods that take a `ColorPairId` can't be used with colors that
+/// weren't started.
+///
+/// [`ColorPairs::allocate`]: struct.ColorPairs.html#method.allocate
+/// [`Color::set_color_pair`]: struct.Color.html#method.set_color_pair
+/// [`Color`]: struct.Color.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ColorPairId {
+    pair: i16,
+}
+
+impl ColorPairId {
+    pub(crate) fn new(pair: i16) -> Self {
+        ColorPairId { pair }
+    }
+
+    /// Get the number of this color pair.
+    pub fn number(self) -> i16 {
+        self.pair
+    }
+}
+
+/// Convert to the attribute that draws text in this color pair.
+///
+/// This corresponds to `COLOR_PAIR`.
+impl From<ColorPairId> for Chtype {
+    fn from(id: ColorPairId) -> Chtype {
+        pancurses::COLOR_PAIR(id.pair as Chtype)
+    }
+}
+
+/// An allocator of color pairs.  It can be accessed via
+/// [`Color::pairs_mut`].
+///
+/// Each combination of foreground and background colors is given one
+/// color pair, which is shared by everyone that allocates it.  A pair is
+/// only reused once it has been freed as many times as it was
+/// allocated.
+///
+/// [`Color::pairs_mut`]: struct.Color.html#method.pairs_mut
+pub struct ColorPairs {
+    /// The colors and number of users of each pair, indexed by pair
+    /// number.  Pair 0 is never allocated.
+    pairs: Vec<Option<(i16, i16, usize)>>,
+    ids: HashMap<(i16, i16), ColorPairId>,
+    limit: i32,
+}
+
+impl ColorPairs {
+    fn new(limit: i32) -> Self {
+        ColorPairs {
+            pairs: vec![None],
+            ids: HashMap::new(),
+            limit: limit.min(i16::MAX as i32 + 1),
+        }
+    }
+
+    /// Get a color pair with the colors `foreground` and `background`.
+    ///
+    /// If a pair with these colors has already been allocated it is
+    /// reused.  Otherwise the lowest unused pair is initialized.
+    ///
+    /// This will fail with [`ErrorKind::Exhausted`] if all of the
+    /// [`Color::color_pairs`] are in use.
+    ///
+    /// This corresponds to `init_pair`.
+    ///
+    /// [`ErrorKind::Exhausted`]: enum.ErrorKind.html#variant.Exhausted
+    /// [`Color::color_pairs`]: struct.Color.html#method.color_pairs
+    pub fn allocate(&mut self, foreground: i16, background: i16) -> Result<ColorPairId, Error> {
+        let (id, new) = self.reserve(foreground, background)?;
+        if new {
+            let r = pancurses::init_pair(id.pair, foreground, background);
+            if let Err(e) = check("init_pair", r) {
+                self.release(id);
+                return Err(
+                    e.with_arguments(format!("{}, {}, {}", id.pair, foreground, background))
+                );
+            }
+        }
+        Ok(id)
+    }
+
+    /// Free a color pair returned by [`allocate`].
+    ///
+    /// Once every user of the pair has freed it, the pair may be reused
+    /// for different colors.
+    ///
+    /// [`allocate`]: struct.ColorPairs.html#method.allocate
+    pub fn free(&mut self, id: ColorPairId) -> Result<(), Error> {
+        if self.release(id) {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::InvalidArgument, "free_pair")
+                .with_arguments(format!("{}", id.pair)))
+        }
+    }
+
+    /// Find the allocated color pair with the colors `foreground` and
+    /// `background`.
+    pub fn find(&self, foreground: i16, background: i16) -> Option<ColorPairId> {
+        self.ids.get(&(foreground, background)).cloned()
+    }
+
+    /// Get the foreground and background colors of an allocated color
+    /// pair.
+    pub fn colors(&self, id: ColorPairId) -> Option<(i16, i16)> {
+        match self.pairs.get(id.pair as usize) {
+            Some(&Some((foreground, background, _))) => Some((foreground, background)),
+            _ => None,
+        }
+    }
+
+    /// Get the number of color pairs in use.
+    pub fn len(&self) -> usize {
+        self.pairs.iter().filter(|pair| pair.is_some()).count()
+    }
+
+    /// Test if no color pairs are in use.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Find or assign a pair.  Returns whether the pair needs to be
+    /// initialized.
+    fn reserve(&mut self, foreground: i16, background: i16) -> Result<(ColorPairId, bool), Error> {
+        if let Some(&id) = self.ids.get(&(foreground, background)) {
+            if let Some(Some((_, _, ref mut users))) = self.pairs.get_mut(id.pair as usize) {
+                *users += 1;
+            }
+            return Ok((id, false));
+        }
+        let pair = match self.pairs.iter().skip(1).position(Option::is_none) {
+            Some(index) => index + 1,
+            None if (self.pairs.len() as i32) < self.limit => {
+                self.pairs.push(None);
+                self.pairs.len() - 1
+            }
+            None => {
+                return Err(Error::new(ErrorKind::Exhausted, "init_pair")
+                    .with_arguments(format!("{}, {}", foreground, background)))
+            }
+        };
+        let id = ColorPairId::new(pair as i16);
+        self.assign(id, foreground, background);
+        Ok((id, true))
+    }
+
+    /// Record that pair `id` has been initialized to `foreground` and
+    /// `background`, replacing any previous colors.
+    ///
+    /// The users of `id` keep it, plus one.  If another pair already has
+    /// these colors it stays in use by its own users, but
+    /// [`find`](#method.find) and [`allocate`](#method.allocate) return
+    /// `id` from now on.
+    fn assign(&mut self, id: ColorPairId, foreground: i16, background: i16) {
+        let index = id.pair as usize;
+        if self.pairs.len() <= index {
+            self.pairs.resize(index + 1, None);
+        }
+        let users = match self.pairs[index].take() {
+            Some((old_foreground, old_background, users)) => {
+                self.unmap(id, old_foreground, old_background);
+                users + 1
+            }
+            None => 1,
+        };
+        self.pairs[index] = Some((foreground, background, users));
+        self.ids.insert((foreground, background), id);
+    }
+
+    /// Stop finding pair `id` by its colors, finding another pair with
+    /// the same colors instead if there is one.
+    fn unmap(&mut self, id: ColorPairId, foreground: i16, background: i16) {
+        if self.ids.get(&(foreground, background)) != Some(&id) {
+            return;
+        }
+        let other = self.pairs.iter().position(|pair| match *pair {
+            Some((f, b, _)) => (f, b) == (foreground, background),
+            None => false,
+        });
+        match other {
+            Some(other) => {
+                self.ids
+                    .insert((foreground, background), ColorPairId::new(other as i16));
+            }
+            None => {
+                self.ids.remove(&(foreground, background));
+            }
+        }
+    }
+
+    /// Release one use of `id`.  Returns `false` if `id` isn't allocated.
+    fn release(&mut self, id: ColorPairId) -> bool {
+        let index = id.pair as usize;
+        match self.pairs.get_mut(index) {
+            Some(&mut Some((foreground, background, ref mut users))) if index != 0 => {
+                *users -= 1;
+                if *users == 0 {
+                    self.pairs[index] = None;
+                    self.unmap(id, foreground, background);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Color subsystem.  It is returned by [`Curses::start_color`] and can
+/// be accessed afterwards via [`Curses::try_color`].
+///
+/// [`Curses::start_color`]: struct.Curses.html#method.start_color
+/// [`Curses::try_color`]: struct.Curses.html#method.try_color
 pub struct Color {
-    marker: PhantomData<()>,
+    pairs: ColorPairs,
+    default_colors: bool,
 }
 
 impl Color {
     pub(crate) fn new() -> Self {
         Color {
-            marker: PhantomData,
+            pairs: ColorPairs::new(pancurses::COLOR_PAIRS()),
+            default_colors: false,
+        }
+    }
+
+    /// Get the palette text with ANSI escape sequences is drawn with.
+    pub(crate) fn palette(&self) -> Palette {
+        Palette {
+            colors: self.max_colors(),
+            default_colors: self.default_colors,
         }
     }
 
+    /// Get the allocator of color pairs.
+    pub fn pairs(&self) -> &ColorPairs {
+        &self.pairs
+    }
+    /// Get the allocator of color pairs.
+    pub fn pairs_mut(&mut self) -> &mut ColorPairs {
+        &mut self.pairs
+    }
+
     /// The maximum number of colors supported.
     ///
     /// This corresponds to `COLORS`.
     pub fn max_colors(&self) -> i32 {
         pancurses::COLORS()
     }
-    /// Get the `n`th color pair.
+    /// Get the attribute that draws text in the color pair `id`.
     ///
     /// This corresponds to `COLOR_PAIR`.
-    pub fn color_pair<T: Into<Chtype>>(&self, n: T) -> Chtype {
-        pancurses::COLOR_PAIR(n.into())
+    pub fn color_pair(&self, id: ColorPairId) -> Chtype {
+        id.into()
     }
     /// Get the number of color pairs.
     ///
@@ -65,31 +304,130 @@ impl Color {
         pancurses::can_change_color()
     }
     /// Tell the curses instance to use default colors.
-    pub fn use_default_colors(&mut self) -> Result<(), ()> {
-        check(pancurses::use_default_colors())
+    pub fn use_default_colors(&mut self) -> Result<(), Error> {
+        check("use_default_colors", pancurses::use_default_colors())?;
+        self.default_colors = true;
+        Ok(())
+    }
+    /// Test if [`use_default_colors`] has been called, making color `-1`
+    /// the terminal's default color.
+    ///
+    /// [`use_default_colors`]: struct.Color.html#method.use_default_colors
+    pub fn uses_default_colors(&self) -> bool {
+        self.default_colors
     }
     /// Set the nth color to a certain [`ColorContent`].
     ///
     /// This corresponds to `init_color`.
     ///
     /// [`ColorContent`]: struct.ColorContent.html
-    pub fn set_color(&mut self, color: i16, color_content: ColorContent) -> Result<(), ()> {
-        check(pancurses::init_color(
-            color,
-            color_content.red,
-            color_content.green,
-            color_content.blue,
-        ))
+    pub fn set_color(&mut self, color: i16, color_content: ColorContent) -> Result<(), Error> {
+        check(
+            "init_color",
+            pancurses::init_color(
+                color,
+                color_content.red,
+                color_content.green,
+                color_content.blue,
+            ),
+        )
     }
     /// Set the `color_pair` to a combination of the `foregrond` and `background` colors.
     ///
+    /// This overrides any colors the pair was given by [`ColorPairs`];
+    /// prefer [`ColorPairs::allocate`] so that pairs aren't shared by
+    /// accident.  The pair is marked as in use once more so that it won't
+    /// be allocated for other colors until it is freed.  Another pair
+    /// that already has these colors stays valid.
+    ///
     /// This corresponds to `init_pair`.
+    ///
+    /// [`ColorPairs`]: struct.ColorPairs.html
+    /// [`ColorPairs::allocate`]: struct.ColorPairs.html#method.allocate
     pub fn set_color_pair(
         &mut self,
         color_pair: i16,
         foreground: i16,
         background: i16,
-    ) -> Result<(), ()> {
-        check(pancurses::init_pair(color_pair, foreground, background))
+    ) -> Result<ColorPairId, Error> {
+        if color_pair <= 0 || color_pair as i32 >= self.pairs.limit {
+            return Err(Error::new(ErrorKind::OutOfBounds, "init_pair")
+                .with_arguments(format!("{}, {}, {}", color_pair, foreground, background)));
+        }
+        check(
+            "init_pair",
+            pancurses::init_pair(color_pair, foreground, background),
+        )?;
+        let id = ColorPairId::new(color_pair);
+        self.pairs.assign(id, foreground, background);
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocation() {
+        let mut pairs = ColorPairs::new(3);
+        let (red, new) = pairs.reserve(COLOR_RED, COLOR_BLACK).unwrap();
+        assert!(new);
+        assert_eq!(red.number(), 1);
+        assert_eq!(pairs.reserve(COLOR_RED, COLOR_BLACK).unwrap(), (red, false));
+        let (blue, _) = pairs.reserve(COLOR_BLUE, COLOR_BLACK).unwrap();
+        assert_eq!(blue.number(), 2);
+        assert_eq!(
+            pairs.reserve(COLOR_GREEN, COLOR_BLACK).unwrap_err().kind(),
+            ErrorKind::Exhausted
+        );
+
+        assert!(pairs.free(red).is_ok());
+        assert_eq!(pairs.colors(red), Some((COLOR_RED, COLOR_BLACK)));
+        assert!(pairs.free(red).is_ok());
+        assert_eq!(pairs.colors(red), None);
+        assert!(pairs.free(red).is_err());
+        assert!(pairs.free(ColorPairId::default()).is_err());
+
+        let (green, _) = pairs.reserve(COLOR_GREEN, COLOR_BLACK).unwrap();
+        assert_eq!(green, red);
+        assert_eq!(pairs.find(COLOR_GREEN, COLOR_BLACK), Some(green));
+        assert_eq!(pairs.len(), 2);
+    }
+
+    #[test]
+    fn assign_replaces_colors() {
+        let mut pairs = ColorPairs::new(8);
+        let (red, _) = pairs.reserve(COLOR_RED, COLOR_BLACK).unwrap();
+        pairs.assign(red, COLOR_CYAN, COLOR_BLACK);
+        assert_eq!(pairs.find(COLOR_RED, COLOR_BLACK), None);
+        assert_eq!(pairs.find(COLOR_CYAN, COLOR_BLACK), Some(red));
+        // Both the allocation and the assignment use the pair.
+        assert!(pairs.free(red).is_ok());
+        assert_eq!(pairs.colors(red), Some((COLOR_CYAN, COLOR_BLACK)));
+        assert!(pairs.free(red).is_ok());
+        assert_eq!(pairs.colors(red), None);
+        assert!(pairs.is_empty());
+    }
+
+    #[test]
+    fn assign_keeps_pairs_with_the_same_colors() {
+        let mut pairs = ColorPairs::new(8);
+        let (red, _) = pairs.reserve(COLOR_RED, COLOR_BLACK).unwrap();
+        let five = ColorPairId::new(5);
+        pairs.assign(five, COLOR_RED, COLOR_BLACK);
+        assert_eq!(pairs.colors(red), Some((COLOR_RED, COLOR_BLACK)));
+        assert_eq!(pairs.find(COLOR_RED, COLOR_BLACK), Some(five));
+        assert_eq!(pairs.len(), 2);
+
+        // Pair 1 isn't reused while it is still in use.
+        let (blue, _) = pairs.reserve(COLOR_BLUE, COLOR_BLACK).unwrap();
+        assert_eq!(blue.number(), 2);
+
+        assert!(pairs.free(five).is_ok());
+        assert_eq!(pairs.find(COLOR_RED, COLOR_BLACK), Some(red));
+        assert!(pairs.free(red).is_ok());
+        assert_eq!(pairs.find(COLOR_RED, COLOR_BLACK), None);
+        assert_eq!(pairs.len(), 1);
     }
 }
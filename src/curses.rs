@@ -1,11 +1,18 @@
 use color::Color;
+use error::*;
+use event::Event;
 use general::*;
-use initialize::end_window;
-use std::sync::Mutex;
+use initialize::{end_window, install_panic_hook};
+use key::{self, Key};
+use mouse::MouseEvent;
+use pad::Pad;
+use point::*;
 use std::time::Duration;
-use window::Window;
+use sys;
+use window::{WeakWindow, Window};
 
 /// The visibility of the cursor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(i32)]
 pub enum CursorVisibility {
     Invisible = 0,
@@ -30,6 +37,7 @@ impl BitsPerSecond {
 /// The input buffering mode.
 ///
 /// This allows us to control which of `raw` and `cbreak` modes are applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InputBufferingMode {
     /// This is the default and means that curses buffers input until a new line
     /// is read.
@@ -52,24 +60,32 @@ pub enum InputBufferingMode {
     UnbufferedNoSignals,
 }
 
-/// The curses instance.  To initialize the curses instance, call [`initscr`].
+/// The curses instance.  To initialize the curses instance, use a
+/// [`CursesBuilder`] or call [`initscr`].
 ///
 /// Many curses functions have been renamed for one reason or another.  All
 /// renamed functions state the curses function they corollate to.
 ///
+/// [`CursesBuilder`]: struct.CursesBuilder.html
 /// [`initscr`]: fn.initscr.html
 pub struct Curses {
     window: Window,
-    key_name_mutex: Mutex<()>,
     color: Option<Color>,
+    mouse_mask: MouseMask,
+    layouts: Vec<(WeakWindow, Box<LayoutFn>)>,
 }
 
+/// Computes the start and size of a `Window` from the size of the
+/// terminal.
+type LayoutFn = dyn Fn(Dimension) -> (Point, Dimension) + Send + Sync;
+
 impl Curses {
     pub(crate) fn new(window: Window) -> Self {
         Curses {
             window,
-            key_name_mutex: Mutex::new(()),
             color: None,
+            mouse_mask: 0,
+            layouts: Vec::new(),
         }
     }
 
@@ -90,19 +106,126 @@ impl Curses {
         &mut self.window
     }
 
+    /// Create a new top-level [`Window`] at `point` on the screen.
+    ///
+    /// Unlike [`Window::create_sub_window`], the new [`Window`] has its
+    /// own memory.  This makes it suitable for popups and dialogs that
+    /// overlap other `Window`s without changing their contents.
+    ///
+    /// To display the new [`Window`], refresh it as normal.  When
+    /// multiple `Window`s overlap, use [`Window::refresh_virtual_screen`]
+    /// on each (in order from bottom to top) followed by [`update`].
+    ///
+    /// If a dimension of `size` is `0`, the `Window` will extend to the
+    /// edge of the screen.
+    ///
+    /// This corresponds to `newwin`.  Note that the arguments have been
+    /// reordered to be more consistent with other functions.
+    ///
+    /// [`Window`]: struct.Window.html
+    /// [`Window::create_sub_window`]: struct.Window.html#method.create_sub_window
+    /// [`Window::refresh_virtual_screen`]: struct.Window.html#method.refresh_virtual_screen
+    /// [`update`]: struct.Curses.html#method.update
+    pub fn create_window<P: Into<Point>, D: Into<Dimension>>(
+        &self,
+        point: P,
+        size: D,
+    ) -> Result<Window, Error> {
+        let p = point.into();
+        let d = size.into();
+        match sys::Window::newwin(d.rows, d.columns, p.y, p.x) {
+            Some(w) => Ok(Window::with_parent(w, &self.window)),
+            None => {
+                let screen = self.window.size();
+                let kind = if p.y < 0
+                    || p.x < 0
+                    || d.rows < 0
+                    || d.columns < 0
+                    || p.y + d.rows > screen.rows
+                    || p.x + d.columns > screen.columns
+                {
+                    ErrorKind::OutOfBounds
+                } else {
+                    ErrorKind::Failed
+                };
+                Err(Error::new(kind, "newwin").with_arguments(format!("{:?}, {:?}", p, d)))
+            }
+        }
+    }
+
+    /// Create a new [`Pad`] of the given size.
+    ///
+    /// Unlike a [`Window`], a [`Pad`] may be larger than the terminal.
+    ///
+    /// This corresponds to `newpad`.
+    ///
+    /// [`Pad`]: struct.Pad.html
+    /// [`Window`]: struct.Window.html
+    pub fn create_pad<D: Into<Dimension>>(&self, size: D) -> Result<Pad, Error> {
+        let d = size.into();
+        match sys::Window::newpad(d.rows, d.columns) {
+            Some(w) => Ok(Pad::new(Window::with_parent(w, &self.window))),
+            None => Err(Error::new(ErrorKind::Failed, "newpad").with_arguments(format!("{:?}", d))),
+        }
+    }
+
     /// Check if the terminal has support for colors.
     pub fn has_colors(&self) -> bool {
         pancurses::has_colors()
     }
     /// Start the color subsystem.
     ///
-    /// If it has already been started, this does nothing.
-    pub fn start_color(&mut self) -> Result<(), ()> {
+    /// If it has already been started, this does nothing.  Either way the
+    /// started [`Color`] subsystem is returned.
+    ///
+    /// [`Color`]: struct.Color.html
+    pub fn start_color(&mut self) -> Result<&mut Color, Error> {
         if self.color.is_none() {
-            check(pancurses::start_color())?;
+            check("start_color", pancurses::start_color())?;
             self.color = Some(Color::new());
         }
-        Ok(())
+        Ok(self.color.as_mut().unwrap())
+    }
+    /// Get an immutable reference to the [`Color`] subsystem if
+    /// [`start_color`] has successfully completed.
+    ///
+    /// Code that doesn't control how curses was started can use this to
+    /// fall back to drawing without colors.
+    ///
+    /// [`Color`]: struct.Color.html
+    /// [`start_color`]: struct.Curses.html#method.start_color
+    pub fn try_color(&self) -> Option<&Color> {
+        self.color.as_ref()
+    }
+    /// Get a mutable reference to the [`Color`] subsystem if
+    /// [`start_color`] has successfully completed.
+    ///
+    /// [`Color`]: struct.Color.html
+    /// [`start_color`]: struct.Curses.html#method.start_color
+    pub fn try_color_mut(&mut self) -> Option<&mut Color> {
+        self.color.as_mut()
+    }
+    /// Get an immutable reference to the [`Color`] subsystem.
+    ///
+    /// This fails with [`ErrorKind::ColorNotStarted`] if [`start_color`]
+    /// has not successfully completed yet.
+    ///
+    /// [`Color`]: struct.Color.html
+    /// [`ErrorKind::ColorNotStarted`]: enum.ErrorKind.html#variant.ColorNotStarted
+    /// [`start_color`]: struct.Curses.html#method.start_color
+    pub fn require_color(&self) -> Result<&Color, Error> {
+        self.color.as_ref().ok_or_else(color_not_started)
+    }
+    /// Get a mutable reference to the [`Color`] subsystem.
+    ///
+    /// This fails with [`ErrorKind::ColorNotStarted`] if [`start_color`]
+    /// has not successfully completed yet.
+    ///
+    /// [`Color`]: struct.Color.html
+    /// [`ErrorKind::ColorNotStarted`]: enum.ErrorKind.html#variant.ColorNotStarted
+    /// [`start_color`]: struct.Curses.html#method.start_color
+    pub fn require_color_mut(&mut self) -> Result<&mut Color, Error> {
+        self.color.as_mut().ok_or_else(color_not_started)
     }
     /// Get an immutable reference to the [`Color`] subsystem.
     ///
@@ -110,9 +233,9 @@ impl Curses {
     ///
     /// [`Color`]: struct.Color.html
     /// [`start_color`]: struct.Curses.html#method.start_color
+    #[deprecated(since = "0.6.0", note = "use try_color or the result of start_color")]
     pub fn color(&self) -> &Color {
-        self.color
-            .as_ref()
+        self.try_color()
             .expect("Color subsystem has not yet been successfully started")
     }
     /// Get a mutable reference to the [`Color`] subsystem.
@@ -121,19 +244,20 @@ impl Curses {
     ///
     /// [`Color`]: struct.Color.html
     /// [`start_color`]: struct.Curses.html#method.start_color
+    #[deprecated(
+        since = "0.6.0",
+        note = "use try_color_mut or the result of start_color"
+    )]
     pub fn color_mut(&mut self) -> &mut Color {
-        self.color
-            .as_mut()
+        self.try_color_mut()
             .expect("Color subsystem has not yet been successfully started")
     }
 
     /// Set the visibility of the cursor.
     ///
     /// This corresponds of `curs_set`.
-    pub fn set_cursor_visibility(&mut self, visibility: CursorVisibility) -> Result<(), ()> {
-        check(pancurses::curs_set(unsafe {
-            std::mem::transmute(visibility)
-        }))
+    pub fn set_cursor_visibility(&mut self, visibility: CursorVisibility) -> Result<(), Error> {
+        check("curs_set", pancurses::curs_set(visibility as i32))
     }
 
     /// Save the current terminal state as program mode (in curses).
@@ -143,8 +267,8 @@ impl Curses {
     /// This corresponds of `def_prog_mode`.
     ///
     /// [`initscr`]: fn.initscr.html
-    pub fn define_program_mode(&mut self) -> Result<(), ()> {
-        check(pancurses::def_prog_mode())
+    pub fn define_program_mode(&mut self) -> Result<(), Error> {
+        check("def_prog_mode", pancurses::def_prog_mode())
     }
     /// Save the current terminal state as shell mode (not in curses).
     ///
@@ -153,20 +277,20 @@ impl Curses {
     /// This corresponds of `def_shell_mode`.
     ///
     /// [`initscr`]: fn.initscr.html
-    pub fn define_shell_mode(&mut self) -> Result<(), ()> {
-        check(pancurses::def_shell_mode())
+    pub fn define_shell_mode(&mut self) -> Result<(), Error> {
+        check("def_shell_mode", pancurses::def_shell_mode())
     }
     /// Restore the terminal to program mode (in curses).
     ///
     /// This corresponds of `reset_prog_mode`.
-    pub fn restore_program_mode(&mut self) -> Result<(), ()> {
-        check(pancurses::reset_prog_mode())
+    pub fn restore_program_mode(&mut self) -> Result<(), Error> {
+        check("reset_prog_mode", pancurses::reset_prog_mode())
     }
     /// Restore the terminal to program mode (not in curses).
     ///
     /// This corresponds of `reset_shell_mode`.
-    pub fn restore_shell_mode(&mut self) -> Result<(), ()> {
-        check(pancurses::reset_shell_mode())
+    pub fn restore_shell_mode(&mut self) -> Result<(), Error> {
+        check("reset_shell_mode", pancurses::reset_shell_mode())
     }
 
     /// Get the output rate of the terminal in bits per second.
@@ -178,15 +302,15 @@ impl Curses {
         }
     }
     /// Insert a millisecond pause in output.  *Don't use this extensively.*
-    pub fn delay_output(&mut self, time: Duration) -> Result<(), ()> {
-        check(pancurses::delay_output(as_millis(time)))
+    pub fn delay_output(&mut self, time: Duration) -> Result<(), Error> {
+        check("delay_output", pancurses::delay_output(as_millis(time)))
     }
 
     /// Push updates from the virtual screen to the physical screen.
     ///
     /// This corresponds of `doupdate`.
-    pub fn update(&mut self) -> Result<(), ()> {
-        check(pancurses::doupdate())
+    pub fn update(&mut self) -> Result<(), Error> {
+        check("doupdate", pancurses::doupdate())
     }
 
     /// Control whether characters typed by the user are written to the screen
@@ -199,11 +323,11 @@ impl Curses {
     /// program and not echoed to the screen.
     ///
     /// This corresponds of `echo` and `noecho`.
-    pub fn set_echo_input(&mut self, echo: bool) -> Result<(), ()> {
+    pub fn set_echo_input(&mut self, echo: bool) -> Result<(), Error> {
         if echo {
-            check(pancurses::echo())
+            check("echo", pancurses::echo())
         } else {
-            check(pancurses::noecho())
+            check("noecho", pancurses::noecho())
         }
     }
     /// Set the input buffering mode.
@@ -213,21 +337,21 @@ impl Curses {
     /// This corresponds of `cbreak`, `nocbreak`, `raw`, and `noraw`.
     ///
     /// [`InputBufferingMode`]: enum.InputBufferingMode.html
-    pub fn set_input_buffering_mode(&mut self, mode: InputBufferingMode) -> Result<(), ()> {
+    pub fn set_input_buffering_mode(&mut self, mode: InputBufferingMode) -> Result<(), Error> {
         match mode {
             InputBufferingMode::Buffered => {
-                let l = check(pancurses::noraw());
-                let r = check(pancurses::nocbreak());
+                let l = check("noraw", pancurses::noraw());
+                let r = check("nocbreak", pancurses::nocbreak());
                 l.and(r)
             }
             InputBufferingMode::UnbufferedWithSignals => {
-                let l = check(pancurses::noraw());
-                let r = check(pancurses::cbreak());
+                let l = check("noraw", pancurses::noraw());
+                let r = check("cbreak", pancurses::cbreak());
                 l.and(r)
             }
             InputBufferingMode::UnbufferedNoSignals => {
-                let l = check(pancurses::cbreak());
-                let r = check(pancurses::raw());
+                let l = check("cbreak", pancurses::cbreak());
+                let r = check("raw", pancurses::raw());
                 l.and(r)
             }
         }
@@ -243,18 +367,18 @@ impl Curses {
     /// `Input::KeyEnter`).
     ///
     /// This corresponds of `nl` and `nonl`.
-    pub fn set_translate_new_lines(&mut self, translate: bool) -> Result<(), ()> {
+    pub fn set_translate_new_lines(&mut self, translate: bool) -> Result<(), Error> {
         if translate {
-            check(pancurses::nl())
+            check("nl", pancurses::nl())
         } else {
-            check(pancurses::nonl())
+            check("nonl", pancurses::nonl())
         }
     }
     /// Throw away all unread key events.
     ///
     /// This corresponds of `flushinp`.
-    pub fn flush_input(&mut self) -> Result<(), ()> {
-        check(pancurses::flushinp())
+    pub fn flush_input(&mut self) -> Result<(), Error> {
+        check("flushinp", pancurses::flushinp())
     }
     /// [`read_char`] will block for at most `duration` and wait for input.
     ///
@@ -272,12 +396,13 @@ impl Curses {
     /// [`disable_cbreak`]: struct.Curses.html#method.disable_cbreak
     /// [`Window::set_timeout`]: struct.Window.html#method.set_timeout
     /// [`Window`]: struct.Window.html
-    pub fn set_timeout(&mut self, duration: Duration) -> Result<(), ()> {
+    pub fn set_timeout(&mut self, duration: Duration) -> Result<(), Error> {
         let tenths = as_millis(duration) / 100;
-        if tenths < 1 || tenths > 255 {
-            Err(())?
+        if !(1..=255).contains(&tenths) {
+            return Err(Error::new(ErrorKind::InvalidArgument, "halfdelay")
+                .with_arguments(format!("{:?}", duration)));
         }
-        check(pancurses::half_delay(tenths))
+        check("halfdelay", pancurses::half_delay(tenths))
     }
 
     /// End the instance of curses, allowing for error handling outside of
@@ -288,32 +413,239 @@ impl Curses {
     /// This disposes of the main `Window`.
     ///
     /// This corresponds of `endwin`.
-    pub fn end_curses(self) -> Result<(), ()> {
+    pub fn end_curses(self) -> Result<(), Error> {
         let r = end_window();
         std::mem::forget(self);
         r
     }
 
+    /// Temporarily return the terminal to shell mode while `f` runs.
+    ///
+    /// This is useful for running other programs that use the terminal,
+    /// such as `$EDITOR` or `less`.  Afterwards the terminal is returned
+    /// to program mode and the entire screen is repainted, including
+    /// every `Window` that was displayed.
+    ///
+    /// This corresponds to `def_prog_mode`, `endwin`, `reset_prog_mode`,
+    /// and `wrefresh(curscr)`.
+    pub fn suspend<F: FnOnce() -> R, R>(&mut self, f: F) -> Result<R, Error> {
+        self.define_program_mode()?;
+        check("endwin", pancurses::endwin())?;
+        let result = f();
+        self.restore_program_mode()?;
+        check("clearok", sys::clear_physical_screen())?;
+        self.update()?;
+        Ok(result)
+    }
+
+    /// Stop the process and return to the shell, as if `Control+Z` was
+    /// pressed.  When the process is continued (for example by `fg`), the
+    /// screen is restored as in [`suspend`].
+    ///
+    /// When signals are enabled (see [`set_input_buffering_mode`]), curses
+    /// handles `Control+Z` itself.  Otherwise `Control+Z` is read as the
+    /// key `C-z` and this method can be used to handle it.
+    ///
+    /// This sends `SIGTSTP` to the process group.
+    ///
+    /// [`suspend`]: struct.Curses.html#method.suspend
+    /// [`set_input_buffering_mode`]: struct.Curses.html#method.set_input_buffering_mode
+    #[cfg(unix)]
+    pub fn background(&mut self) -> Result<(), Error> {
+        let r = self.suspend(|| unsafe {
+            // Stop the rest of the process group while ignoring the
+            // signal ourselves.  A signal sent to the whole process may be
+            // handled by another thread after the handler is restored.
+            let handler = libc::signal(libc::SIGTSTP, libc::SIG_IGN);
+            let mut r = libc::kill(0, libc::SIGTSTP);
+            // Then stop this process via a signal to this thread, which is
+            // handled before `raise` returns.  This uses the default action
+            // rather than the handler curses may have installed.
+            libc::signal(libc::SIGTSTP, libc::SIG_DFL);
+            if r == 0 {
+                r = libc::raise(libc::SIGTSTP);
+            }
+            libc::signal(libc::SIGTSTP, handler);
+            r
+        })?;
+        if r == 0 {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::Failed, "kill"))
+        }
+    }
+    /// Stop the process and return to the shell, as if `Control+Z` was
+    /// pressed.
+    ///
+    /// This is not supported on Windows and always fails with
+    /// [`ErrorKind::Unsupported`].
+    ///
+    /// [`ErrorKind::Unsupported`]: enum.ErrorKind.html#variant.Unsupported
+    #[cfg(windows)]
+    pub fn background(&mut self) -> Result<(), Error> {
+        Err(Error::new(ErrorKind::Unsupported, "kill"))
+    }
+
+    /// Install a panic hook that ends curses before the panic message is
+    /// printed.
+    ///
+    /// Without this hook the panic message is printed into the curses
+    /// screen, where it is lost when the screen is cleared, and the
+    /// terminal stays in the modes curses put it in until `Curses` is
+    /// dropped.  The hook returns the terminal to shell mode and then
+    /// calls the previously installed panic hook.
+    ///
+    /// The hook is only installed once and stays installed after curses
+    /// is ended; it does nothing while curses isn't running.
+    pub fn install_panic_hook(&mut self) {
+        install_panic_hook();
+    }
+
     /// Flash the terminal screen.  If not possible, an alert is sounded.
     ///
     /// Returns `Ok` if flashing succeeds, and `Err` otherwise.
-    pub fn flash(&mut self) -> Result<(), ()> {
-        check(pancurses::flash())
+    pub fn flash(&mut self) -> Result<(), Error> {
+        check("flash", pancurses::flash())
     }
 
     /// Get a string representing a key code.
     ///
     /// This corresponds of `keyname`.
+    ///
+    /// For a structured representation of keys, see [`KeyEvent`].
+    ///
+    /// [`KeyEvent`]: struct.KeyEvent.html
     pub fn key_name(&self, key_code: i32) -> Option<String> {
-        let _key_name = self.key_name_mutex.lock().unwrap();
-        pancurses::keyname(key_code)
+        key::key_name(key_code)
+    }
+
+    /// Read an input event from `stdscr`.
+    ///
+    /// Keys are read via [`Window::read_key`].  Mouse events are read via
+    /// [`mouse_read`].  When the terminal is resized, `stdscr` is resized
+    /// before [`Event::Resize`] is returned.  Other `Window`s are laid out
+    /// again by passing them to [`relayout`].
+    ///
+    /// Returns `None` if no input is available.
+    ///
+    /// [`Window::read_key`]: struct.Window.html#method.read_key
+    /// [`mouse_read`]: struct.Curses.html#method.mouse_read
+    /// [`Event::Resize`]: enum.Event.html#variant.Resize
+    /// [`relayout`]: struct.Curses.html#method.relayout
+    pub fn read_event(&mut self) -> Result<Option<Event>, Error> {
+        let key = match self.window.read_key() {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        match key.key {
+            Key::Mouse => self.mouse_read().map(|mouse| Some(Event::Mouse(mouse))),
+            Key::Resize => {
+                let size = self.handle_resize()?;
+                Ok(Some(Event::Resize {
+                    rows: size.rows,
+                    columns: size.columns,
+                }))
+            }
+            _ => Ok(Some(Event::Key(key))),
+        }
+    }
+
+    /// Move and resize `window` whenever the terminal is resized.
+    ///
+    /// `layout` is given the new size of the terminal and returns the new
+    /// start (on the screen) and size of `window`.  It is applied to
+    /// `window` by [`relayout`], which should be called when
+    /// [`read_event`] returns [`Event::Resize`].  `window` is not kept
+    /// alive; the `layout` is forgotten once `window` is dropped.
+    /// Registering another `layout` for `window` replaces the previous
+    /// one.
+    ///
+    /// [`relayout`]: struct.Curses.html#method.relayout
+    /// [`read_event`]: struct.Curses.html#method.read_event
+    /// [`Event::Resize`]: enum.Event.html#variant.Resize
+    pub fn relayout_on_resize<F>(&mut self, window: &Window, layout: F)
+    where
+        F: Fn(Dimension) -> (Point, Dimension) + Send + Sync + 'static,
+    {
+        self.layouts
+            .retain(|(registered, _)| registered.is_alive() && !registered.refers_to(window));
+        self.layouts.push((window.downgrade(), Box::new(layout)));
     }
 
-    /// Get the status of the mouse.
+    /// Get the start and size the layout registered with
+    /// [`relayout_on_resize`] gives `window` for the current size of
+    /// `stdscr`.
+    ///
+    /// Returns `None` if no layout is registered for `window`.
+    ///
+    /// [`relayout_on_resize`]: struct.Curses.html#method.relayout_on_resize
+    pub fn layout_of(&self, window: &Window) -> Option<(Point, Dimension)> {
+        let size = self.window.size();
+        self.layouts
+            .iter()
+            .find(|(registered, _)| registered.refers_to(window))
+            .map(|(_, layout)| layout(size))
+    }
+
+    /// Move and resize each of `windows` as the layout registered with
+    /// [`relayout_on_resize`] gives it for the current size of `stdscr`.
+    ///
+    /// `Window`s without a layout are left alone.  All layouts are
+    /// applied even if some fail; the first error is returned.
+    ///
+    /// [`relayout_on_resize`]: struct.Curses.html#method.relayout_on_resize
+    pub fn relayout(&mut self, windows: &mut [&mut Window]) -> Result<(), Error> {
+        self.layouts.retain(|(registered, _)| registered.is_alive());
+        let mut result = Ok(());
+        for window in windows.iter_mut() {
+            if let Some((start, size)) = self.layout_of(window) {
+                // Shrinking must happen before moving towards the edge of
+                // the screen and growing after moving away from it.
+                let r = window
+                    .resize(size)
+                    .and_then(|_| window.move_window(start))
+                    .or_else(|_| window.move_window(start).and_then(|_| window.resize(size)));
+                if result.is_ok() {
+                    result = r;
+                }
+            }
+        }
+        result
+    }
+
+    /// Update curses after the terminal has been resized.
+    fn handle_resize(&mut self) -> Result<Dimension, Error> {
+        // ncurses resizes the screen itself before returning
+        // `KEY_RESIZE`, but PDCurses must be told to.
+        if cfg!(windows) {
+            self.resize_terminal(0, 0)?;
+        }
+        Ok(self.window.size())
+    }
+
+    /// Read the mouse event that caused [`Key::Mouse`] to be read.
+    ///
+    /// Events that aren't recognized fail with
+    /// [`ErrorKind::Unsupported`]; use [`mouse_read_raw`] to handle them.
     ///
     /// This corresponds of `getmouse`.
-    pub fn mouse_read(&self) -> Result<MouseEvent, ()> {
-        pancurses::getmouse().map_err(|_| ())
+    ///
+    /// [`Key::Mouse`]: enum.Key.html#variant.Mouse
+    /// [`ErrorKind::Unsupported`]: enum.ErrorKind.html#variant.Unsupported
+    /// [`mouse_read_raw`]: struct.Curses.html#method.mouse_read_raw
+    pub fn mouse_read(&self) -> Result<MouseEvent, Error> {
+        let raw = self.mouse_read_raw()?;
+        let reporting_position = self.mouse_mask & REPORT_MOUSE_POSITION != 0;
+        MouseEvent::from_raw(&raw, reporting_position).ok_or_else(|| {
+            Error::new(ErrorKind::Unsupported, "getmouse")
+                .with_arguments(format!("{:#x}", raw.bstate))
+        })
+    }
+    /// Read the mouse event as the raw `MEVENT`.
+    ///
+    /// This corresponds of `getmouse`.
+    pub fn mouse_read_raw(&self) -> Result<RawMouseEvent, Error> {
+        pancurses::getmouse().map_err(|_| Error::new(ErrorKind::Failed, "getmouse"))
     }
     /// Get the maximum time between press and release events for it
     /// to be recognized as a click.
@@ -329,7 +661,7 @@ impl Curses {
     /// this behavior will change in the future.
     ///
     /// This corresponds of `mouseinterval`.
-    pub fn set_mouse_interval(&mut self, interval: Duration) -> Result<(), ()> {
+    pub fn set_mouse_interval(&mut self, interval: Duration) -> Result<(), Error> {
         pancurses::mouseinterval(as_millis(interval));
         Ok(())
     }
@@ -347,31 +679,63 @@ impl Curses {
         &mut self,
         mask: MouseMask,
         old_mask: Option<&mut MouseMask>,
-    ) -> Result<MouseMask, ()> {
+    ) -> Result<MouseMask, Error> {
         let old_mask_ptr = match old_mask {
             Some(mask) => mask,
             None => std::ptr::null_mut(),
         };
-        Ok(pancurses::mousemask(mask, old_mask_ptr))
+        self.mouse_mask = pancurses::mousemask(mask, old_mask_ptr);
+        Ok(self.mouse_mask)
+    }
+
+    /// Set how long to wait for the rest of an escape sequence after
+    /// `ESC` is read.
+    ///
+    /// Function keys are sent by terminals as escape sequences.  A lone
+    /// `ESC` is only read as [`Key::Escape`] after this delay passes.
+    ///
+    /// This corresponds of `set_escdelay`.
+    ///
+    /// [`Key::Escape`]: enum.Key.html#variant.Escape
+    #[cfg(unix)]
+    pub fn set_escape_delay(&mut self, delay: Duration) -> Result<(), Error> {
+        let delay = as_millis(delay);
+        check("set_escdelay", sys::set_escdelay(delay))
+            .map_err(|e| e.with_arguments(delay.to_string()))
+    }
+    /// Set how long to wait for the rest of an escape sequence after
+    /// `ESC` is read.
+    ///
+    /// PDCurses reads function keys directly from the console, so this is
+    /// not supported on Windows and always fails with
+    /// [`ErrorKind::Unsupported`].
+    ///
+    /// This corresponds of `set_escdelay`.
+    ///
+    /// [`ErrorKind::Unsupported`]: enum.ErrorKind.html#variant.Unsupported
+    #[cfg(windows)]
+    pub fn set_escape_delay(&mut self, delay: Duration) -> Result<(), Error> {
+        Err(Error::new(ErrorKind::Unsupported, "set_escdelay")
+            .with_arguments(as_millis(delay).to_string()))
     }
 
     /// Sleep for a certain number of milliseconds.
     ///
     /// This corresponds of `napms`.
-    pub fn sleep(&mut self, duration: Duration) -> Result<(), ()> {
-        check(pancurses::napms(as_millis(duration)))
+    pub fn sleep(&mut self, duration: Duration) -> Result<(), Error> {
+        check("napms", pancurses::napms(as_millis(duration)))
     }
 
     /// Attempt to resize the terminal.
     ///
     /// This corresponds of `resize_term`.
-    pub fn resize_terminal(&mut self, rows: i32, columns: i32) -> Result<(), ()> {
-        check(pancurses::resize_term(rows, columns))
+    pub fn resize_terminal(&mut self, rows: i32, columns: i32) -> Result<(), Error> {
+        check("resize_term", pancurses::resize_term(rows, columns))
     }
 
     /// Attempt to beep the terminal.
-    pub fn beep(&mut self) -> Result<(), ()> {
-        check(pancurses::beep())
+    pub fn beep(&mut self) -> Result<(), Error> {
+        check("beep", pancurses::beep())
     }
 
     /// Control whether characters with `A_BLINK` will actually blink the screen
@@ -383,26 +747,36 @@ impl Curses {
     /// When disabled, Allow the terminal to either actually blink or set a high
     /// intensity background when `blink` is called.
     ///
-    /// This is only supported on Windows.
+    /// This is only supported on Windows.  On other platforms this does
+    /// nothing.
     ///
     /// This corresponds of `set_blink`.
-    pub fn set_force_blink(&mut self, blink: bool) -> Result<(), ()> {
-        check(pancurses::set_blink(blink))
+    pub fn set_force_blink(&mut self, blink: bool) -> Result<(), Error> {
+        check("set_blink", pancurses::set_blink(blink))
     }
 
     /// Set the title of the terminal.
     ///
-    /// This is only supported on Windows.
-    pub fn set_title<T: AsRef<str>>(&mut self, title: T) -> Result<(), ()> {
-        Ok(pancurses::set_title(title.as_ref()))
+    /// This is only supported on Windows.  On other platforms this does
+    /// nothing.
+    pub fn set_title<T: AsRef<str>>(&mut self, title: T) -> Result<(), Error> {
+        pancurses::set_title(title.as_ref());
+        Ok(())
     }
 }
 
-/// Call [`end_window`] and `unwrap` the result.
+fn color_not_started() -> Error {
+    Error::new(ErrorKind::ColorNotStarted, "start_color")
+}
+
+/// Call [`end_curses`], ignoring any error.
+///
+/// This never panics, even if curses has already been ended by the
+/// panic hook.  Use [`end_curses`] to handle errors.
 ///
-/// [`end_window`]: struct.Curses.html#method.end_window
+/// [`end_curses`]: struct.Curses.html#method.end_curses
 impl Drop for Curses {
     fn drop(&mut self) {
-        end_window().unwrap();
+        let _ = end_window();
     }
 }
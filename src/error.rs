@@ -0,0 +1,115 @@
+use std::error;
+use std::fmt;
+
+/// The category of an [`Error`].
+///
+/// [`Error`]: struct.Error.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The curses function returned `ERR` without any more specific
+    /// reason being known.
+    Failed,
+    /// A [`Point`] or [`Dimension`] lies outside of the `Window`.
+    ///
+    /// [`Point`]: struct.Point.html
+    /// [`Dimension`]: struct.Dimension.html
+    OutOfBounds,
+    /// Curses has already been initialized.
+    AlreadyInitialized,
+    /// Curses has not been initialized or has already been ended.
+    NotInitialized,
+    /// The [`Color`] subsystem has not been started.
+    ///
+    /// [`Color`]: struct.Color.html
+    ColorNotStarted,
+    /// The operation is not supported by the terminal or platform.
+    Unsupported,
+    /// An argument was rejected before being passed to curses.
+    InvalidArgument,
+    /// The `Window` cannot be deleted because sub windows are still
+    /// using its memory.
+    WindowInUse,
+    /// All of the resources of some kind, such as color pairs, are in
+    /// use.
+    Exhausted,
+}
+
+impl ErrorKind {
+    fn description(self) -> &'static str {
+        match self {
+            ErrorKind::Failed => "failed",
+            ErrorKind::OutOfBounds => "out of bounds",
+            ErrorKind::AlreadyInitialized => "curses is already initialized",
+            ErrorKind::NotInitialized => "curses is not initialized",
+            ErrorKind::ColorNotStarted => "the color subsystem has not been started",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::InvalidArgument => "invalid argument",
+            ErrorKind::WindowInUse => "the window has sub windows that are still alive",
+            ErrorKind::Exhausted => "no more are available",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// An error returned by a curses function.
+///
+/// This records which curses function failed, the arguments it was
+/// given (if they are relevant to the failure), and an [`ErrorKind`]
+/// categorizing the failure.
+///
+/// [`ErrorKind`]: enum.ErrorKind.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    function: &'static str,
+    arguments: Option<String>,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind, function: &'static str) -> Self {
+        Error {
+            kind,
+            function,
+            arguments: None,
+        }
+    }
+
+    pub(crate) fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub(crate) fn with_arguments(mut self, arguments: String) -> Self {
+        self.arguments = Some(arguments);
+        self
+    }
+
+    /// Get the category of this `Error`.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+    /// Get the name of the curses function that failed.
+    pub fn function(&self) -> &'static str {
+        self.function
+    }
+    /// Get the arguments that were rejected, if any were recorded.
+    pub fn arguments(&self) -> Option<&str> {
+        self.arguments.as_deref()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.arguments {
+            Some(ref arguments) => write!(f, "{}({}): {}", self.function, arguments, self.kind),
+            None => write!(f, "{}: {}", self.function, self.kind),
+        }
+    }
+}
+
+impl error::Error for Error {}
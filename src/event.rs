@@ -0,0 +1,18 @@
+use key::KeyEvent;
+use mouse::MouseEvent;
+
+/// An input event read via [`Curses::read_event`].
+///
+/// [`Curses::read_event`]: struct.Curses.html#method.read_event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A key was pressed.
+    Key(KeyEvent),
+    /// The mouse was used.
+    Mouse(MouseEvent),
+    /// The terminal was resized.  `stdscr` has already been resized;
+    /// other `Window`s are laid out again with [`Curses::relayout`].
+    ///
+    /// [`Curses::relayout`]: struct.Curses.html#method.relayout
+    Resize { rows: i32, columns: i32 },
+}
@@ -22,11 +22,13 @@ pub use pancurses::{
 };
 pub type Chtype = pancurses::chtype;
 pub type MouseMask = pancurses::mmask_t;
-pub type MouseEvent = pancurses::MEVENT;
+pub type RawMouseEvent = pancurses::MEVENT;
 
-pub(crate) fn check(r: i32) -> Result<(), ()> {
+use error::{Error, ErrorKind};
+
+pub(crate) fn check(function: &'static str, r: i32) -> Result<(), Error> {
     if r == pancurses::ERR {
-        Err(())
+        Err(Error::new(ErrorKind::Failed, function))
     } else {
         Ok(())
     }
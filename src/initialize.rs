@@ -1,20 +1,52 @@
 use curses::Curses;
+use error::*;
 use general::*;
-use std::sync::Mutex;
+use std::panic;
+use std::sync::{Mutex, Once, PoisonError, TryLockError};
+use sys;
 use window::Window;
 
 lazy_static! {
     static ref INITIALIZED: Mutex<bool> = Mutex::new(false);
 }
 
-pub(crate) fn end_window() -> Result<(), ()> {
-    let mut initialized = INITIALIZED.lock().unwrap();
+static PANIC_HOOK: Once = Once::new();
+
+pub(crate) fn end_window() -> Result<(), Error> {
+    let mut initialized = INITIALIZED.lock().unwrap_or_else(PoisonError::into_inner);
     if *initialized {
-        check(pancurses::endwin())?;
+        check("endwin", pancurses::endwin())?;
         *initialized = false;
         Ok(())
     } else {
-        Err(())
+        Err(Error::new(ErrorKind::NotInitialized, "endwin"))
+    }
+}
+
+/// Install a panic hook that ends curses before the panic message is
+/// printed.  The hook is only installed once and chains to the hook that
+/// was installed before it.
+pub(crate) fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous(info);
+        }));
+    });
+}
+
+/// End curses if it is running.  This must not panic or block as it is
+/// called while panicking.
+fn restore_terminal() {
+    let mut initialized = match INITIALIZED.try_lock() {
+        Ok(initialized) => initialized,
+        Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(TryLockError::WouldBlock) => return,
+    };
+    if *initialized {
+        pancurses::endwin();
+        *initialized = false;
     }
 }
 
@@ -22,16 +54,26 @@ pub(crate) fn end_window() -> Result<(), ()> {
 ///
 /// This is a correlary of the c function `initscr`.
 ///
+/// This fails with [`ErrorKind::AlreadyInitialized`] if curses is already
+/// running.
+///
+/// Prefer [`CursesBuilder`], which also applies common startup options.
+///
 /// [`Curses`]: struct.Curses.html
-pub fn initscr() -> Result<Curses, ()> {
+/// [`CursesBuilder`]: struct.CursesBuilder.html
+/// [`ErrorKind::AlreadyInitialized`]: enum.ErrorKind.html#variant.AlreadyInitialized
+pub fn initscr() -> Result<Curses, Error> {
     {
-        let mut initialized = INITIALIZED.lock().unwrap();
+        let mut initialized = INITIALIZED.lock().unwrap_or_else(PoisonError::into_inner);
         if *initialized {
-            return Err(());
+            return Err(Error::new(ErrorKind::AlreadyInitialized, "initscr"));
         } else {
             *initialized = true;
         }
     }
-    let w = pancurses::initscr();
-    Ok(Curses::new(Window::new(w)))
+    // `pancurses` prepares the platform before calling `initscr`.  The
+    // `pancurses::Window` it returns refers to `stdscr` and doesn't
+    // delete it when dropped.
+    drop(pancurses::initscr());
+    Ok(Curses::new(Window::new(sys::Window::stdscr())))
 }
@@ -0,0 +1,492 @@
+use general::*;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref KEY_NAME: Mutex<()> = Mutex::new(());
+}
+
+/// Get a string representing a key code.
+///
+/// `keyname` returns a pointer to a static buffer so calls are serialized.
+pub(crate) fn key_name(key_code: i32) -> Option<String> {
+    let _key_name = KEY_NAME.lock().unwrap();
+    pancurses::keyname(key_code)
+}
+
+/// A key on the keyboard.
+///
+/// Letters are always represented by the character they produce, so
+/// `Shift+a` is `Char('A')` without the [`shift`] modifier.
+///
+/// [`shift`]: struct.Modifiers.html#structfield.shift
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Key {
+    /// A key that produces a character.
+    Char(char),
+    /// A function key.  `F(1)` is `F1`.
+    F(u8),
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    Backspace,
+    Enter,
+    Tab,
+    Escape,
+    /// A mouse event is ready to be read via [`Curses::mouse_read`].
+    ///
+    /// [`Curses::mouse_read`]: struct.Curses.html#method.mouse_read
+    Mouse,
+    /// The terminal has been resized.
+    ///
+    /// [`Curses::read_event`] handles this key by resizing `stdscr` and
+    /// returning [`Event::Resize`].
+    ///
+    /// [`Curses::read_event`]: struct.Curses.html#method.read_event
+    /// [`Event::Resize`]: enum.Event.html#variant.Resize
+    Resize,
+    /// A key code that isn't recognized.
+    Unknown(i32),
+}
+
+/// The modifier keys held while a [`Key`] was pressed.
+///
+/// [`Key`]: enum.Key.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl Modifiers {
+    /// No modifiers.
+    pub const NONE: Modifiers = Modifiers {
+        ctrl: false,
+        alt: false,
+        shift: false,
+    };
+    /// Only `Control`.
+    pub const CTRL: Modifiers = Modifiers {
+        ctrl: true,
+        alt: false,
+        shift: false,
+    };
+    /// Only `Alt` (also known as `Meta`).
+    pub const ALT: Modifiers = Modifiers {
+        ctrl: false,
+        alt: true,
+        shift: false,
+    };
+    /// Only `Shift`.
+    pub const SHIFT: Modifiers = Modifiers {
+        ctrl: false,
+        alt: false,
+        shift: true,
+    };
+
+    /// Test if no modifiers are held.
+    pub fn is_empty(self) -> bool {
+        self == Modifiers::NONE
+    }
+
+    /// Combine the modifiers held in `self` and `other`.
+    pub fn union(self, other: Modifiers) -> Modifiers {
+        Modifiers {
+            ctrl: self.ctrl || other.ctrl,
+            alt: self.alt || other.alt,
+            shift: self.shift || other.shift,
+        }
+    }
+
+    /// Decode the modifier parameter of an xterm style key (`kLFT5` is
+    /// `Control+Left`).
+    fn from_xterm_parameter(parameter: u8) -> Option<Modifiers> {
+        if (2..=8).contains(&parameter) {
+            let bits = parameter - 1;
+            Some(Modifiers {
+                shift: bits & 1 != 0,
+                alt: bits & 2 != 0,
+                ctrl: bits & 4 != 0,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A key press along with the modifiers held at the time.
+///
+/// A `KeyEvent` can be read directly via [`Window::read_key`] or
+/// converted from an [`Input`].
+///
+/// `KeyEvent`s can be written and parsed in the notation used by Emacs:
+/// modifiers are written as prefixes (`C-` for `Control`, `M-` or `A-`
+/// for `Alt`, and `S-` for `Shift`) followed by the name of the key.  For
+/// example `"C-x"`, `"M-Left"`, and `"S-F5"`.
+///
+/// [`Window::read_key`]: struct.Window.html#method.read_key
+/// [`Input`]: enum.Input.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    /// Create a new `KeyEvent`.
+    pub fn new(key: Key, modifiers: Modifiers) -> Self {
+        KeyEvent { key, modifiers }
+    }
+
+    /// Add `modifiers` to this `KeyEvent`.
+    pub fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        KeyEvent {
+            key: self.key,
+            modifiers: self.modifiers.union(modifiers),
+        }
+    }
+
+    /// Decode a single byte or character.
+    ///
+    /// Control characters are converted to the key that was pressed with
+    /// the `Control` modifier.  `Escape` is not treated as an `Alt`
+    /// prefix here; that requires reading the next key.
+    pub(crate) fn from_char(c: char) -> Self {
+        match c {
+            '\t' => Key::Tab.into(),
+            '\n' | '\r' => Key::Enter.into(),
+            '\x1b' => Key::Escape.into(),
+            '\x08' | '\x7f' => Key::Backspace.into(),
+            '\0' => KeyEvent::new(Key::Char(' '), Modifiers::CTRL),
+            '\x01'..='\x1a' => {
+                KeyEvent::new(Key::Char((c as u8 - 1 + b'a') as char), Modifiers::CTRL)
+            }
+            '\x1c'..='\x1f' => {
+                KeyEvent::new(Key::Char((c as u8 - 0x1c + b'\\') as char), Modifiers::CTRL)
+            }
+            c => Key::Char(c).into(),
+        }
+    }
+
+    /// Decode a key code that curses did not recognize by its name.
+    ///
+    /// ncurses names keys with modifiers after their xterm escape
+    /// sequences, for example `kLFT5` is `Control+Left` and `kDC3` is
+    /// `Alt+Delete`.
+    pub(crate) fn from_unknown(code: i32) -> Self {
+        key_name(code)
+            .and_then(|name| KeyEvent::from_extended_name(&name))
+            .unwrap_or_else(|| Key::Unknown(code).into())
+    }
+
+    fn from_extended_name(name: &str) -> Option<Self> {
+        let split = name.find(|c: char| c.is_ascii_digit())?;
+        let modifiers = Modifiers::from_xterm_parameter(name[split..].parse().ok()?)?;
+        let key = match &name[..split] {
+            "kUP" => Key::Up,
+            "kDN" => Key::Down,
+            "kLFT" => Key::Left,
+            "kRIT" => Key::Right,
+            "kHOM" => Key::Home,
+            "kEND" => Key::End,
+            "kPRV" => Key::PageUp,
+            "kNXT" => Key::PageDown,
+            "kIC" => Key::Insert,
+            "kDC" => Key::Delete,
+            _ => return None,
+        };
+        Some(KeyEvent::new(key, modifiers))
+    }
+
+    /// Decode the `n`th function key.
+    ///
+    /// Terminals report modified function keys as higher numbered
+    /// function keys in groups of twelve: `F13` is `Shift+F1`, `F25` is
+    /// `Control+F1`, and so on.
+    pub(crate) fn from_function_key(n: i32) -> Self {
+        let (group, n) = if n <= 0 {
+            (0, 0)
+        } else {
+            ((n - 1) / 12, (n - 1) % 12 + 1)
+        };
+        let modifiers = match group {
+            0 => Modifiers::NONE,
+            1 => Modifiers::SHIFT,
+            2 => Modifiers::CTRL,
+            3 => Modifiers::CTRL.union(Modifiers::SHIFT),
+            4 => Modifiers::ALT,
+            _ => Modifiers::ALT.union(Modifiers::SHIFT),
+        };
+        KeyEvent::new(Key::F(n as u8), modifiers)
+    }
+}
+
+impl From<Key> for KeyEvent {
+    fn from(key: Key) -> Self {
+        KeyEvent::new(key, Modifiers::NONE)
+    }
+}
+
+impl From<Input> for KeyEvent {
+    fn from(input: Input) -> Self {
+        let shift = |key| KeyEvent::new(key, Modifiers::SHIFT);
+        match input {
+            Input::Character(c) => KeyEvent::from_char(c),
+            Input::Unknown(code) => KeyEvent::from_unknown(code),
+            Input::KeyF0 => KeyEvent::from_function_key(0),
+            Input::KeyF1 => KeyEvent::from_function_key(1),
+            Input::KeyF2 => KeyEvent::from_function_key(2),
+            Input::KeyF3 => KeyEvent::from_function_key(3),
+            Input::KeyF4 => KeyEvent::from_function_key(4),
+            Input::KeyF5 => KeyEvent::from_function_key(5),
+            Input::KeyF6 => KeyEvent::from_function_key(6),
+            Input::KeyF7 => KeyEvent::from_function_key(7),
+            Input::KeyF8 => KeyEvent::from_function_key(8),
+            Input::KeyF9 => KeyEvent::from_function_key(9),
+            Input::KeyF10 => KeyEvent::from_function_key(10),
+            Input::KeyF11 => KeyEvent::from_function_key(11),
+            Input::KeyF12 => KeyEvent::from_function_key(12),
+            Input::KeyF13 => KeyEvent::from_function_key(13),
+            Input::KeyF14 => KeyEvent::from_function_key(14),
+            Input::KeyF15 => KeyEvent::from_function_key(15),
+            Input::KeyUp => Key::Up.into(),
+            Input::KeyDown => Key::Down.into(),
+            Input::KeyLeft => Key::Left.into(),
+            Input::KeyRight => Key::Right.into(),
+            Input::KeyHome => Key::Home.into(),
+            Input::KeyEnd | Input::KeyLL => Key::End.into(),
+            Input::KeyPPage | Input::KeyPrevious => Key::PageUp.into(),
+            Input::KeyNPage | Input::KeyNext => Key::PageDown.into(),
+            Input::KeyIC | Input::KeyEIC => Key::Insert.into(),
+            Input::KeyDC => Key::Delete.into(),
+            Input::KeyBackspace => Key::Backspace.into(),
+            Input::KeyEnter => Key::Enter.into(),
+            Input::KeySR => shift(Key::Up),
+            Input::KeySF => shift(Key::Down),
+            Input::KeySLeft => shift(Key::Left),
+            Input::KeySRight => shift(Key::Right),
+            Input::KeySHome => shift(Key::Home),
+            Input::KeySEnd => shift(Key::End),
+            Input::KeySPrevious => shift(Key::PageUp),
+            Input::KeySNext => shift(Key::PageDown),
+            Input::KeySIC => shift(Key::Insert),
+            Input::KeySDC => shift(Key::Delete),
+            Input::KeyBTab => shift(Key::Tab),
+            Input::KeyMouse => Key::Mouse.into(),
+            Input::KeyResize => Key::Resize.into(),
+            input => {
+                let index = SPECIAL_KEY_CODES
+                    .iter()
+                    .position(|i| *i == input)
+                    .unwrap_or(0) as i32;
+                let code = if index + KEY_OFFSET <= KEY_F15 {
+                    index + KEY_OFFSET
+                } else {
+                    index + KEY_OFFSET + 48
+                };
+                Key::Unknown(code).into()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Key::Char(' ') => f.write_str("Space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::F(n) => write!(f, "F{}", n),
+            Key::Up => f.write_str("Up"),
+            Key::Down => f.write_str("Down"),
+            Key::Left => f.write_str("Left"),
+            Key::Right => f.write_str("Right"),
+            Key::Home => f.write_str("Home"),
+            Key::End => f.write_str("End"),
+            Key::PageUp => f.write_str("PageUp"),
+            Key::PageDown => f.write_str("PageDown"),
+            Key::Insert => f.write_str("Insert"),
+            Key::Delete => f.write_str("Delete"),
+            Key::Backspace => f.write_str("Backspace"),
+            Key::Enter => f.write_str("Enter"),
+            Key::Tab => f.write_str("Tab"),
+            Key::Escape => f.write_str("Escape"),
+            Key::Mouse => f.write_str("Mouse"),
+            Key::Resize => f.write_str("Resize"),
+            Key::Unknown(code) => write!(f, "Unknown{}", code),
+        }
+    }
+}
+
+impl fmt::Display for KeyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.ctrl {
+            f.write_str("C-")?;
+        }
+        if self.modifiers.alt {
+            f.write_str("M-")?;
+        }
+        if self.modifiers.shift {
+            f.write_str("S-")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// An error parsing a [`Key`] or [`KeyEvent`].
+///
+/// [`Key`]: enum.Key.html
+/// [`KeyEvent`]: struct.KeyEvent.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseKeyError {
+    input: String,
+}
+
+impl ParseKeyError {
+    pub(crate) fn new(input: &str) -> Self {
+        ParseKeyError {
+            input: input.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid key: {:?}", self.input)
+    }
+}
+
+impl error::Error for ParseKeyError {}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    /// Parse the name of a key.  Names are case insensitive.
+    ///
+    /// In addition to the names produced by `Display`, the Emacs names
+    /// `SPC`, `TAB`, `RET`, `ESC`, and `DEL` are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(c));
+        }
+        let key = match s.to_ascii_lowercase().as_str() {
+            "space" | "spc" => Key::Char(' '),
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" | "prior" => Key::PageUp,
+            "pagedown" | "pgdn" | "next" => Key::PageDown,
+            "insert" | "ins" => Key::Insert,
+            "delete" => Key::Delete,
+            "backspace" | "del" => Key::Backspace,
+            "enter" | "return" | "ret" => Key::Enter,
+            "tab" => Key::Tab,
+            "escape" | "esc" => Key::Escape,
+            "mouse" => Key::Mouse,
+            "resize" => Key::Resize,
+            name => match name.strip_prefix('f').map(str::parse::<u8>) {
+                Some(Ok(n)) => Key::F(n),
+                _ => return Err(ParseKeyError::new(s)),
+            },
+        };
+        Ok(key)
+    }
+}
+
+impl FromStr for KeyEvent {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::NONE;
+        let mut rest = s;
+        while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+            match rest.as_bytes()[0] {
+                b'C' => modifiers.ctrl = true,
+                b'M' | b'A' => modifiers.alt = true,
+                b'S' => modifiers.shift = true,
+                _ => break,
+            }
+            rest = &rest[2..];
+        }
+        let key = rest.parse().map_err(|_| ParseKeyError::new(s))?;
+        let event = KeyEvent::new(key, modifiers);
+        // Shifted letters are represented by the character they produce.
+        Ok(match event.key {
+            Key::Char(c) if modifiers.shift && c.is_alphabetic() => KeyEvent {
+                key: Key::Char(c.to_uppercase().next().unwrap_or(c)),
+                modifiers: Modifiers {
+                    shift: false,
+                    ..modifiers
+                },
+            },
+            _ => event,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> KeyEvent {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_modifiers() {
+        assert_eq!(key("C-x"), KeyEvent::new(Key::Char('x'), Modifiers::CTRL));
+        assert_eq!(key("M-Left"), KeyEvent::new(Key::Left, Modifiers::ALT));
+        assert_eq!(key("S-F5"), KeyEvent::new(Key::F(5), Modifiers::SHIFT));
+        assert_eq!(
+            key("C-M-S-Delete"),
+            KeyEvent::new(
+                Key::Delete,
+                Modifiers {
+                    ctrl: true,
+                    alt: true,
+                    shift: true
+                }
+            )
+        );
+        assert_eq!(key("C--"), KeyEvent::new(Key::Char('-'), Modifiers::CTRL));
+        assert_eq!(key("S-a"), KeyEvent::from(Key::Char('A')));
+        assert!("C-Nope".parse::<KeyEvent>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in &["C-x", "M-Left", "S-F5", "C-M-Space", "Escape", "a", "-"] {
+            assert_eq!(key(s).to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn control_characters() {
+        assert_eq!(KeyEvent::from_char('\x18'), key("C-x"));
+        assert_eq!(KeyEvent::from_char('\x1f'), key("C-_"));
+        assert_eq!(KeyEvent::from_char('\x7f'), key("Backspace"));
+        assert_eq!(KeyEvent::from_char('\r'), key("RET"));
+    }
+
+    #[test]
+    fn modified_keys() {
+        assert_eq!(KeyEvent::from_extended_name("kLFT5"), Some(key("C-Left")));
+        assert_eq!(KeyEvent::from_extended_name("kDC3"), Some(key("M-Delete")));
+        assert_eq!(KeyEvent::from_function_key(17), key("S-F5"));
+        assert_eq!(KeyEvent::from_function_key(25), key("C-F1"));
+        assert_eq!(KeyEvent::from(Input::KeySLeft), key("S-Left"));
+    }
+}
@@ -0,0 +1,379 @@
+use key::*;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use sys;
+use window::Window;
+
+enum Binding<A> {
+    Action(A),
+    Prefix(Keymap<A>),
+}
+
+/// A table mapping sequences of keys to actions.
+///
+/// Sequences are written as [`KeyEvent`]s separated by spaces, for
+/// example `"C-x C-s"`.  Every key but the last in a sequence is a
+/// prefix that is bound to a nested `Keymap`.
+///
+/// Use a [`Dispatcher`] to read keys from a [`Window`] and look them up.
+///
+/// [`KeyEvent`]: struct.KeyEvent.html
+/// [`Dispatcher`]: struct.Dispatcher.html
+/// [`Window`]: struct.Window.html
+pub struct Keymap<A> {
+    bindings: BTreeMap<KeyEvent, Binding<A>>,
+    fallback: Option<A>,
+}
+
+impl<A> Default for Keymap<A> {
+    fn default() -> Self {
+        Keymap::new()
+    }
+}
+
+impl<A> Keymap<A> {
+    /// Create an empty `Keymap`.
+    pub fn new() -> Self {
+        Keymap {
+            bindings: BTreeMap::new(),
+            fallback: None,
+        }
+    }
+
+    /// Bind the sequence of keys `sequence` to `action`.
+    ///
+    /// Any binding of `sequence` or of one of its prefixes is replaced.
+    pub fn bind(&mut self, sequence: &str, action: A) -> Result<(), ParseKeyError> {
+        let keys = parse_sequence(sequence)?;
+        self.bind_keys(&keys, action);
+        Ok(())
+    }
+
+    /// Bind the sequence of keys `keys` to `action`.
+    ///
+    /// Any binding of `keys` or of one of its prefixes is replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    pub fn bind_keys(&mut self, keys: &[KeyEvent], action: A) {
+        let (last, prefix) = keys
+            .split_last()
+            .expect("Cannot bind an empty key sequence");
+        self.prefix_map(prefix)
+            .bindings
+            .insert(*last, Binding::Action(action));
+    }
+
+    /// Bind the sequence of keys `sequence` to the nested `keymap`.
+    ///
+    /// Keys bound in `keymap` are looked up after `sequence` is pressed.
+    pub fn bind_prefix(&mut self, sequence: &str, keymap: Keymap<A>) -> Result<(), ParseKeyError> {
+        let keys = parse_sequence(sequence)?;
+        let (last, prefix) = keys.split_last().expect("parse_sequence is never empty");
+        self.prefix_map(prefix)
+            .bindings
+            .insert(*last, Binding::Prefix(keymap));
+        Ok(())
+    }
+
+    /// Remove the binding of `sequence`.
+    ///
+    /// Returns the action that was bound, if any.
+    pub fn unbind(&mut self, sequence: &str) -> Result<Option<A>, ParseKeyError> {
+        let keys = parse_sequence(sequence)?;
+        let (last, prefix) = keys.split_last().expect("parse_sequence is never empty");
+        let mut keymap = self;
+        for key in prefix {
+            keymap = match keymap.bindings.get_mut(key) {
+                Some(Binding::Prefix(keymap)) => keymap,
+                _ => return Ok(None),
+            };
+        }
+        if let Some(Binding::Action(_)) = keymap.bindings.get(last) {
+            if let Some(Binding::Action(action)) = keymap.bindings.remove(last) {
+                return Ok(Some(action));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Set the action used when a key that isn't bound in this
+    /// `Keymap` is pressed.
+    ///
+    /// Fallbacks are not inherited by nested prefix maps.
+    pub fn set_fallback(&mut self, fallback: Option<A>) {
+        self.fallback = fallback;
+    }
+
+    /// Look up the action bound to the sequence of keys `keys`.
+    pub fn lookup(&self, keys: &[KeyEvent]) -> Option<&A> {
+        let (last, prefix) = keys.split_last()?;
+        let mut keymap = self;
+        for key in prefix {
+            keymap = match keymap.bindings.get(key) {
+                Some(Binding::Prefix(keymap)) => keymap,
+                _ => return None,
+            };
+        }
+        match keymap.bindings.get(last) {
+            Some(Binding::Action(action)) => Some(action),
+            _ => None,
+        }
+    }
+
+    /// List every bound sequence and its action, in key order.
+    ///
+    /// Sequences are written in the same notation accepted by [`bind`],
+    /// making this suitable for a help screen.
+    ///
+    /// [`bind`]: struct.Keymap.html#method.bind
+    pub fn describe(&self) -> Vec<(String, &A)> {
+        let mut description = Vec::new();
+        self.describe_into(&mut Vec::new(), &mut description);
+        description
+    }
+
+    fn describe_into<'a>(&'a self, prefix: &mut Vec<KeyEvent>, out: &mut Vec<(String, &'a A)>) {
+        for (key, binding) in &self.bindings {
+            prefix.push(*key);
+            match binding {
+                Binding::Action(action) => out.push((format_sequence(prefix), action)),
+                Binding::Prefix(keymap) => keymap.describe_into(prefix, out),
+            }
+            prefix.pop();
+        }
+    }
+
+    /// Get the nested `Keymap` for `prefix`, replacing any actions bound
+    /// to the keys along the way.
+    fn prefix_map(&mut self, prefix: &[KeyEvent]) -> &mut Keymap<A> {
+        let mut keymap = self;
+        for key in prefix {
+            let binding = keymap
+                .bindings
+                .entry(*key)
+                .or_insert_with(|| Binding::Prefix(Keymap::new()));
+            if let Binding::Action(_) = binding {
+                *binding = Binding::Prefix(Keymap::new());
+            }
+            keymap = match binding {
+                Binding::Prefix(keymap) => keymap,
+                Binding::Action(_) => unreachable!(),
+            };
+        }
+        keymap
+    }
+}
+
+fn parse_sequence(sequence: &str) -> Result<Vec<KeyEvent>, ParseKeyError> {
+    let keys = sequence
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<KeyEvent>, _>>()?;
+    if keys.is_empty() {
+        Err(ParseKeyError::new(sequence))
+    } else {
+        Ok(keys)
+    }
+}
+
+fn format_sequence(keys: &[KeyEvent]) -> String {
+    keys.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The result of giving a key to a [`Dispatcher`].
+///
+/// [`Dispatcher`]: struct.Dispatcher.html
+#[derive(Debug, PartialEq, Eq)]
+pub enum Dispatch<'a, A> {
+    /// The keys pressed form a sequence bound to an action.
+    Action(&'a A),
+    /// The keys pressed are a prefix of a bound sequence.  More keys
+    /// are required.
+    Pending,
+    /// The keys pressed aren't bound.  `fallback` is the fallback of
+    /// the `Keymap` the last key was looked up in.
+    Unbound {
+        keys: Vec<KeyEvent>,
+        fallback: Option<&'a A>,
+    },
+    /// The chord timeout expired before the sequence was completed.
+    TimedOut { keys: Vec<KeyEvent> },
+}
+
+/// Reads keys and looks them up in a [`Keymap`].
+///
+/// The `Dispatcher` remembers the prefix keys that have been pressed so
+/// far.  These can be shown to the user, for example in a status line,
+/// via [`pending`].
+///
+/// [`Keymap`]: struct.Keymap.html
+/// [`pending`]: struct.Dispatcher.html#method.pending
+pub struct Dispatcher<'a, A: 'a> {
+    keymap: &'a Keymap<A>,
+    pending: Vec<KeyEvent>,
+    chord_timeout: Option<Duration>,
+}
+
+impl<'a, A> Dispatcher<'a, A> {
+    /// Create a `Dispatcher` that looks up keys in `keymap`.
+    pub fn new(keymap: &'a Keymap<A>) -> Self {
+        Dispatcher {
+            keymap,
+            pending: Vec::new(),
+            chord_timeout: None,
+        }
+    }
+
+    /// Set the maximum time to wait for the next key of a sequence after
+    /// a prefix has been pressed.
+    ///
+    /// `None`, the default, waits indefinitely.
+    pub fn set_chord_timeout(&mut self, timeout: Option<Duration>) {
+        self.chord_timeout = timeout;
+    }
+
+    /// Get the prefix keys pressed so far.
+    pub fn pending(&self) -> &[KeyEvent] {
+        &self.pending
+    }
+
+    /// Describe the prefix keys pressed so far, for example `"C-x"`.
+    pub fn describe_pending(&self) -> String {
+        format_sequence(&self.pending)
+    }
+
+    /// Forget the prefix keys pressed so far.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Read a key from `window` via [`Window::read_key`] and look it up.
+    ///
+    /// While a prefix is pending, the chord timeout is applied via
+    /// [`Window::set_timeout`] and then the `Window`'s previous timeout
+    /// is restored.  Without a chord timeout, the read blocks even if
+    /// the `Window` doesn't.
+    ///
+    /// Returns `None` if no key was available and no prefix is pending.
+    ///
+    /// [`Window::read_key`]: struct.Window.html#method.read_key
+    /// [`Window::set_timeout`]: struct.Window.html#method.set_timeout
+    pub fn read(&mut self, window: &mut Window) -> Option<Dispatch<'a, A>> {
+        let key = if self.pending.is_empty() {
+            window.read_key()
+        } else {
+            // Without a chord timeout, this blocks even if the `Window`
+            // doesn't.
+            let delay = sys::get_delay(window.as_ptr());
+            window.set_timeout(self.chord_timeout);
+            let key = window.read_key();
+            sys::wtimeout(window.as_ptr(), delay);
+            key
+        };
+        match key {
+            Some(key) => Some(self.feed(key)),
+            None if self.pending.is_empty() => None,
+            None => Some(Dispatch::TimedOut {
+                keys: self.pending.split_off(0),
+            }),
+        }
+    }
+
+    /// Look up `key`, after the keys already pending.
+    pub fn feed(&mut self, key: KeyEvent) -> Dispatch<'a, A> {
+        let mut keymap = self.keymap;
+        for key in &self.pending {
+            keymap = match keymap.bindings.get(key) {
+                Some(Binding::Prefix(keymap)) => keymap,
+                _ => unreachable!("pending keys are always prefixes"),
+            };
+        }
+        self.pending.push(key);
+        match keymap.bindings.get(&key) {
+            Some(Binding::Action(action)) => {
+                self.pending.clear();
+                Dispatch::Action(action)
+            }
+            Some(Binding::Prefix(_)) => Dispatch::Pending,
+            None => Dispatch::Unbound {
+                keys: self.pending.split_off(0),
+                fallback: keymap.fallback.as_ref(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> KeyEvent {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn nested_sequences() {
+        let mut keymap = Keymap::new();
+        keymap.bind("C-x C-s", "save").unwrap();
+        keymap.bind("C-x C-c", "quit").unwrap();
+        keymap.bind("C-f", "forward").unwrap();
+        keymap.set_fallback(Some("insert"));
+
+        let mut dispatcher = Dispatcher::new(&keymap);
+        assert_eq!(dispatcher.feed(key("C-x")), Dispatch::Pending);
+        assert_eq!(dispatcher.describe_pending(), "C-x");
+        assert_eq!(dispatcher.feed(key("C-s")), Dispatch::Action(&"save"));
+        assert!(dispatcher.pending().is_empty());
+        assert_eq!(dispatcher.feed(key("C-f")), Dispatch::Action(&"forward"));
+        assert_eq!(
+            dispatcher.feed(key("a")),
+            Dispatch::Unbound {
+                keys: vec![key("a")],
+                fallback: Some(&"insert"),
+            }
+        );
+        dispatcher.feed(key("C-x"));
+        assert_eq!(
+            dispatcher.feed(key("a")),
+            Dispatch::Unbound {
+                keys: vec![key("C-x"), key("a")],
+                fallback: None,
+            }
+        );
+    }
+
+    #[test]
+    fn rebinding_prefixes() {
+        let mut keymap = Keymap::new();
+        keymap.bind("C-x", 1).unwrap();
+        keymap.bind("C-x C-s", 2).unwrap();
+        assert_eq!(keymap.lookup(&[key("C-x")]), None);
+        assert_eq!(keymap.lookup(&[key("C-x"), key("C-s")]), Some(&2));
+        assert_eq!(keymap.unbind("C-x").unwrap(), None);
+        assert_eq!(keymap.unbind("C-x C-s").unwrap(), Some(2));
+        assert!(keymap.bind("", 3).is_err());
+    }
+
+    #[test]
+    fn describe() {
+        let mut help = Keymap::new();
+        help.bind("k", "describe-key").unwrap();
+        let mut keymap = Keymap::new();
+        keymap.bind_prefix("C-h", help).unwrap();
+        keymap.bind("C-x C-s", "save").unwrap();
+        keymap.bind("M-x", "command").unwrap();
+        assert_eq!(
+            keymap.describe(),
+            vec![
+                ("C-h k".to_string(), &"describe-key"),
+                ("M-x".to_string(), &"command"),
+                ("C-x C-s".to_string(), &"save"),
+            ]
+        );
+    }
+}
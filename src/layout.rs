@@ -0,0 +1,306 @@
+use curses::Curses;
+use error::*;
+use point::*;
+use rect::Rect;
+use window::Window;
+
+/// The direction a [`Layout`] splits its area in.
+///
+/// [`Layout`]: struct.Layout.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Regions are placed side by side, left to right.
+    Horizontal,
+    /// Regions are stacked top to bottom.
+    Vertical,
+}
+
+/// How much of a [`Layout`]'s area one region takes along the
+/// direction of the `Layout`.
+///
+/// Space is first given to `Length`, `Percentage` and the minimum of
+/// `Min`, in order, until it runs out.  The space left over is shared by
+/// `Min`, `Max` and `Fill` regions in proportion to their weights (1
+/// for `Min` and `Max`), without growing a `Max` region past its
+/// maximum.
+///
+/// [`Layout`]: struct.Layout.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// Exactly this many cells.
+    Length(i32),
+    /// This percentage of the area.
+    Percentage(u16),
+    /// At least this many cells, growing with weight 1.
+    Min(i32),
+    /// At most this many cells, growing with weight 1.
+    Max(i32),
+    /// A share of the left over space with this weight.
+    Fill(u16),
+}
+
+impl Constraint {
+    /// The weight the region grows with, if it grows.
+    fn weight(self) -> i32 {
+        match self {
+            Constraint::Length(_) | Constraint::Percentage(_) => 0,
+            Constraint::Min(_) | Constraint::Max(_) => 1,
+            Constraint::Fill(weight) => i32::from(weight),
+        }
+    }
+}
+
+/// Splits a [`Rect`] into regions following a list of [`Constraint`]s.
+///
+/// A `Layout` is a plain description, so splitting again after the
+/// terminal is resized is a single call to [`split`].  Layouts are
+/// nested by splitting one of the regions of another `Layout`.
+///
+/// [`Rect`]: struct.Rect.html
+/// [`Constraint`]: enum.Constraint.html
+/// [`split`]: struct.Layout.html#method.split
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    direction: Direction,
+    constraints: Vec<Constraint>,
+    margin: Dimension,
+    spacing: i32,
+}
+
+impl Layout {
+    /// Create a `Layout` splitting in `direction`.
+    pub fn new<I: IntoIterator<Item = Constraint>>(direction: Direction, constraints: I) -> Self {
+        Layout {
+            direction,
+            constraints: constraints.into_iter().collect(),
+            margin: Dimension {
+                rows: 0,
+                columns: 0,
+            },
+            spacing: 0,
+        }
+    }
+    /// Create a `Layout` placing regions side by side.
+    pub fn horizontal<I: IntoIterator<Item = Constraint>>(constraints: I) -> Self {
+        Layout::new(Direction::Horizontal, constraints)
+    }
+    /// Create a `Layout` stacking regions top to bottom.
+    pub fn vertical<I: IntoIterator<Item = Constraint>>(constraints: I) -> Self {
+        Layout::new(Direction::Vertical, constraints)
+    }
+
+    /// Leave `rows` empty rows at the top and bottom of the area and
+    /// `columns` empty columns at its left and right.
+    pub fn margin(mut self, rows: i32, columns: i32) -> Self {
+        self.margin = Dimension { rows, columns };
+        self
+    }
+    /// Leave `spacing` empty cells between adjacent regions.
+    pub fn spacing(mut self, spacing: i32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Split `area` into one region per constraint.
+    ///
+    /// Regions never overlap and never extend past `area`, so regions
+    /// are empty if `area` is too small.
+    pub fn split<R: Into<Rect>>(&self, area: R) -> Vec<Rect> {
+        let area = area.into().inset(self.margin.rows, self.margin.columns);
+        let (start, total) = match self.direction {
+            Direction::Horizontal => (area.origin.x, area.size.columns),
+            Direction::Vertical => (area.origin.y, area.size.rows),
+        };
+        let count = self.constraints.len() as i32;
+        let spacing = self.spacing.max(0);
+        let available = (total - spacing * (count - 1).max(0)).max(0);
+        let lengths = solve(&self.constraints, available);
+
+        let mut offset = start;
+        lengths
+            .into_iter()
+            .map(|length| {
+                let rect = match self.direction {
+                    Direction::Horizontal => {
+                        Rect::new((area.origin.y, offset), (area.size.rows, length))
+                    }
+                    Direction::Vertical => {
+                        Rect::new((offset, area.origin.x), (length, area.size.columns))
+                    }
+                };
+                offset = (offset + length + spacing).min(start + total);
+                rect
+            })
+            .collect()
+    }
+
+    /// Split the whole of `window`.
+    ///
+    /// The regions are relative to `window`, as used by its drawing
+    /// methods and [`Window::create_derived_window`].
+    ///
+    /// [`Window::create_derived_window`]: struct.Window.html#method.create_derived_window
+    pub fn split_window(&self, window: &Window) -> Vec<Rect> {
+        self.split(Rect::new((0, 0), window.size()))
+    }
+
+    /// Create a derived window of `window` for each region.
+    ///
+    /// Empty regions can't be made into windows, so this fails if
+    /// `window` is too small for the layout.
+    ///
+    /// This corresponds to `derwin`.
+    pub fn create_windows(&self, window: &Window) -> Result<Vec<Window>, Error> {
+        self.split_window(window)
+            .into_iter()
+            .map(|rect| window.create_derived_window(rect.origin, rect.size))
+            .collect()
+    }
+
+    /// Lay out `windows` on the screen with this `Layout`, now and
+    /// whenever the terminal is resized.
+    ///
+    /// Window `i` covers region `i` of the screen.  The windows should be
+    /// created via [`Curses::create_window`].  After the terminal is
+    /// resized, pass them to [`Curses::relayout`] again.  See
+    /// [`Curses::relayout_on_resize`].
+    ///
+    /// [`Curses::create_window`]: struct.Curses.html#method.create_window
+    /// [`Curses::relayout`]: struct.Curses.html#method.relayout
+    /// [`Curses::relayout_on_resize`]: struct.Curses.html#method.relayout_on_resize
+    pub fn relayout_on_resize(
+        &self,
+        curses: &mut Curses,
+        windows: &mut [&mut Window],
+    ) -> Result<(), Error> {
+        for (index, window) in windows.iter().enumerate() {
+            let layout = self.clone();
+            curses.relayout_on_resize(window, move |size| {
+                let rect = layout
+                    .split(Rect::new((0, 0), size))
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_default();
+                (rect.origin, rect.size)
+            });
+        }
+        curses.relayout(windows)
+    }
+}
+
+/// Find the length of each constraint so that they fit in `available`.
+fn solve(constraints: &[Constraint], available: i32) -> Vec<i32> {
+    let mut remaining = available;
+    let mut lengths: Vec<i32> = constraints
+        .iter()
+        .map(|constraint| {
+            let wanted = match *constraint {
+                Constraint::Length(length) | Constraint::Min(length) => length,
+                Constraint::Percentage(percent) => available * i32::from(percent) / 100,
+                Constraint::Max(_) | Constraint::Fill(_) => 0,
+            };
+            let length = wanted.max(0).min(remaining);
+            remaining -= length;
+            length
+        })
+        .collect();
+
+    // Share the rest by weight.  A `Max` that would pass its maximum is
+    // fixed at it and the rest is shared again by the others.
+    let mut growing: Vec<usize> = (0..constraints.len())
+        .filter(|&i| constraints[i].weight() > 0)
+        .collect();
+    while remaining > 0 && !growing.is_empty() {
+        let weights: i32 = growing.iter().map(|&i| constraints[i].weight()).sum();
+        let capped: Vec<usize> = growing
+            .iter()
+            .cloned()
+            .filter(|&i| match constraints[i] {
+                Constraint::Max(max) => remaining * constraints[i].weight() / weights >= max,
+                _ => false,
+            })
+            .collect();
+        if capped.is_empty() {
+            let mut shared = 0;
+            for &i in &growing {
+                let share = remaining * constraints[i].weight() / weights;
+                lengths[i] += share;
+                shared += share;
+            }
+            // Hand out what rounding left over one cell at a time.
+            for &i in growing.iter().take((remaining - shared) as usize) {
+                lengths[i] += 1;
+            }
+            break;
+        }
+        for i in capped {
+            if let Constraint::Max(max) = constraints[i] {
+                lengths[i] = max.max(0);
+                remaining -= max.max(0);
+            }
+            growing.retain(|&j| j != i);
+        }
+    }
+    lengths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Constraint::*;
+    use super::*;
+
+    fn lengths(constraints: &[Constraint], total: i32) -> Vec<i32> {
+        Layout::horizontal(constraints.iter().cloned())
+            .split(Rect::new((0, 0), (1, total)))
+            .into_iter()
+            .map(|rect| rect.size.columns)
+            .collect()
+    }
+
+    #[test]
+    fn fixed_and_fill() {
+        assert_eq!(lengths(&[Length(3), Fill(1), Length(2)], 10), [3, 5, 2]);
+        assert_eq!(lengths(&[Percentage(50), Fill(1)], 9), [4, 5]);
+        assert_eq!(lengths(&[Fill(1), Fill(2)], 9), [3, 6]);
+        assert_eq!(lengths(&[Fill(1), Fill(1), Fill(1)], 10), [4, 3, 3]);
+        assert_eq!(lengths(&[Length(3)], 10), [3]);
+    }
+
+    #[test]
+    fn min_and_max() {
+        assert_eq!(lengths(&[Min(4), Fill(1)], 6), [5, 1]);
+        assert_eq!(lengths(&[Min(8), Fill(1)], 6), [6, 0]);
+        assert_eq!(lengths(&[Max(2), Fill(1)], 10), [2, 8]);
+        assert_eq!(lengths(&[Max(8), Fill(1)], 10), [5, 5]);
+        assert_eq!(lengths(&[Max(3), Max(3)], 10), [3, 3]);
+    }
+
+    #[test]
+    fn too_small() {
+        assert_eq!(lengths(&[Length(4), Length(4), Fill(1)], 6), [4, 2, 0]);
+        assert_eq!(lengths(&[Length(4)], 0), [0]);
+    }
+
+    #[test]
+    fn margin_and_spacing() {
+        let rects = Layout::vertical(vec![Length(2), Fill(1)])
+            .margin(1, 2)
+            .spacing(1)
+            .split(Rect::new((10, 10), (8, 10)));
+        assert_eq!(
+            rects,
+            [Rect::new((11, 12), (2, 6)), Rect::new((14, 12), (3, 6))]
+        );
+    }
+
+    #[test]
+    fn nesting() {
+        let area = Rect::new((0, 0), (10, 20));
+        let rows = Layout::vertical(vec![Length(1), Fill(1)]).split(area);
+        let columns = Layout::horizontal(vec![Percentage(25), Fill(1)]).split(rows[1]);
+        assert_eq!(
+            columns,
+            [Rect::new((1, 0), (9, 5)), Rect::new((1, 5), (9, 15))]
+        );
+    }
+}
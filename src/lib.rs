@@ -7,30 +7,90 @@
 //! renamed functions state the curses function they corollate to.
 //!
 //! The outermost class is [`Curses`].  It is initialized via
-//! [`initscr`].  Use its methods to manipulate the curses instance.
+//! [`CursesBuilder`], which also applies the options most programs set
+//! at startup, or via [`initscr`].  Use its methods to manipulate the
+//! curses instance.
 //!
 //! [`Curses`] manages the [`Window`] representing `stdscr`.
 //!
+//! Functions that can fail return an [`Error`] describing which curses
+//! function failed and why.
+//!
+//! Drawing code written against the [`Backend`] trait can be unit tested
+//! on a [`VirtualScreen`], which doesn't need a terminal.  What was
+//! drawn can be compared against a stored file with [`assert_snapshot`].
+//!
+//! [`assert_snapshot`]: fn.assert_snapshot.html
+//! [`Backend`]: trait.Backend.html
 //! [`Curses`]: struct.Curses.html
+//! [`CursesBuilder`]: struct.CursesBuilder.html
+//! [`Error`]: struct.Error.html
 //! [`initscr`]: fn.initscr.html
+//! [`VirtualScreen`]: struct.VirtualScreen.html
 //! [`Window`]: struct.Window.html
 
 extern crate pancurses;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(unix)]
+extern crate libc;
+#[cfg(unix)]
+extern crate ncurses;
+#[cfg(windows)]
+extern crate pdcurses;
+#[cfg(feature = "wide")]
+extern crate unicode_width;
 
+mod error;
+pub use error::*;
 mod general;
 pub use general::*;
 mod initialize;
 pub use initialize::*;
+mod builder;
+pub use builder::*;
 mod point;
 pub use point::*;
+mod rect;
+pub use rect::*;
+mod layout;
+pub use layout::*;
 mod curses;
 pub use curses::*;
 mod color;
 pub use color::*;
 mod window;
 pub use window::*;
+mod wrap;
+pub use wrap::*;
+mod border;
+pub use border::*;
+mod cell;
+pub use cell::*;
+mod pad;
+pub use pad::*;
+mod key;
+pub use key::*;
+mod mouse;
+pub use mouse::*;
+mod keymap;
+pub use keymap::*;
+mod line_editor;
+pub use line_editor::*;
+mod style;
+pub use style::*;
+mod styled_text;
+pub use styled_text::*;
+mod ansi;
+mod event;
+pub use event::*;
+mod backend;
+pub use backend::*;
+mod virtual_screen;
+pub use virtual_screen::*;
+mod snapshot;
+pub use snapshot::*;
+mod sys;
 
 #[cfg(test)]
 mod tests {
@@ -38,6 +98,7 @@ mod tests {
 
     fn type_assert_send<T: Send>() {}
     fn type_assert_sync<T: Sync>() {}
+    fn type_assert_error<T: std::error::Error + Send + Sync + 'static>() {}
 
     #[test]
     fn window_is_send() {
@@ -58,4 +119,24 @@ mod tests {
     fn curses_is_sync() {
         type_assert_sync::<Curses>();
     }
+
+    #[test]
+    fn pad_is_send() {
+        type_assert_send::<Pad>();
+    }
+
+    #[test]
+    fn pad_is_sync() {
+        type_assert_sync::<Pad>();
+    }
+
+    #[test]
+    fn virtual_screen_is_send() {
+        type_assert_send::<VirtualScreen>();
+    }
+
+    #[test]
+    fn error_is_error() {
+        type_assert_error::<Error>();
+    }
 }
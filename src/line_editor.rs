@@ -0,0 +1,538 @@
+use error::*;
+use key::*;
+use point::*;
+use sys;
+use window::Window;
+use wrap::char_width;
+
+/// A function that completes the text before the cursor.
+///
+/// It is given the text before the cursor and returns candidates that
+/// would replace it.
+pub type Completer = Box<dyn FnMut(&str) -> Vec<String> + Send>;
+
+/// A single line text input.
+///
+/// This is a safe replacement for `getstr`.  The line is edited in a
+/// region of a [`Window`] using Emacs style key bindings:
+///
+/// * `Left`/`C-b`, `Right`/`C-f`, `Home`/`C-a`, and `End`/`C-e` move the
+///   cursor.  `M-b`/`C-Left` and `M-f`/`C-Right` move by words.
+/// * `Backspace` and `Delete`/`C-d` delete characters.
+/// * `C-k`, `C-u`, `C-w`/`M-Backspace`, and `M-d` kill text, which can be
+///   yanked back with `C-y`.
+/// * `Insert` toggles between inserting and overwriting characters.
+/// * `Up`/`C-p` and `Down`/`C-n` move through the history.
+/// * `Tab` calls the completion function, if one is set.  Pressing `Tab`
+///   again cycles through the candidates.
+/// * `Enter` accepts the line.  `Escape`, `C-g`, and `C-c` cancel.
+///
+/// Characters outside of ASCII can only be entered when the `wide`
+/// feature is enabled.
+///
+/// [`Window`]: struct.Window.html
+pub struct LineEditor {
+    history: Vec<String>,
+    history_limit: usize,
+    completer: Option<Completer>,
+    overwrite: bool,
+    kill_buffer: Vec<char>,
+}
+
+enum Status {
+    Editing,
+    Accepted,
+    Cancelled,
+}
+
+/// The state of the line currently being edited.
+struct Line {
+    chars: Vec<char>,
+    cursor: usize,
+    scroll: usize,
+    /// The index into the history being shown.  The line being entered
+    /// has an index of `history.len()`.
+    history_index: usize,
+    /// The line being entered while the history is being browsed.
+    saved: Vec<char>,
+    /// The candidates of the previous completion and which one is shown.
+    completions: Option<(Vec<String>, Option<usize>)>,
+}
+
+impl Line {
+    fn new(history_index: usize) -> Self {
+        Line {
+            chars: Vec::new(),
+            cursor: 0,
+            scroll: 0,
+            history_index,
+            saved: Vec::new(),
+            completions: None,
+        }
+    }
+
+    fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    fn set_text(&mut self, chars: Vec<char>) {
+        self.cursor = chars.len();
+        self.chars = chars;
+    }
+
+    fn word_start(&self) -> usize {
+        let mut i = self.cursor;
+        while i > 0 && !self.chars[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+        while i > 0 && self.chars[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+        i
+    }
+
+    fn word_end(&self) -> usize {
+        let mut i = self.cursor;
+        while i < self.chars.len() && !self.chars[i].is_alphanumeric() {
+            i += 1;
+        }
+        while i < self.chars.len() && self.chars[i].is_alphanumeric() {
+            i += 1;
+        }
+        i
+    }
+
+    /// Replace the text before the cursor with `prefix`.
+    fn replace_prefix(&mut self, prefix: &str) {
+        let rest = self.chars.split_off(self.cursor);
+        self.chars = prefix.chars().collect();
+        self.cursor = self.chars.len();
+        self.chars.extend(rest);
+    }
+
+    /// Get the number of columns the characters in `range` take.
+    fn columns(&self, range: std::ops::Range<usize>) -> usize {
+        self.chars[range].iter().cloned().map(char_width).sum()
+    }
+
+    /// Scroll so that the cursor is visible in `width` columns.
+    fn scroll_to_cursor(&mut self, width: usize) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        }
+        // The cursor covers the character under it, or a blank at the end.
+        let cursor_width = self
+            .chars
+            .get(self.cursor)
+            .map_or(1, |&c| char_width(c).max(1));
+        while self.scroll < self.cursor
+            && self.columns(self.scroll..self.cursor) + cursor_width > width
+        {
+            self.scroll += 1;
+        }
+    }
+}
+
+impl Default for LineEditor {
+    fn default() -> Self {
+        LineEditor::new()
+    }
+}
+
+impl LineEditor {
+    /// Create a `LineEditor` with an empty history.
+    pub fn new() -> Self {
+        LineEditor {
+            history: Vec::new(),
+            history_limit: 100,
+            completer: None,
+            overwrite: false,
+            kill_buffer: Vec::new(),
+        }
+    }
+
+    /// Get the lines in the history, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Add `line` to the history.
+    ///
+    /// Empty lines and lines equal to the most recent line are ignored.
+    pub fn add_history<S: Into<String>>(&mut self, line: S) {
+        let line = line.into();
+        if line.is_empty() || self.history.last() == Some(&line) {
+            return;
+        }
+        self.history.push(line);
+        self.trim_history();
+    }
+
+    /// Set the maximum number of lines kept in the history.
+    ///
+    /// The default is 100.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        self.trim_history();
+    }
+
+    fn trim_history(&mut self) {
+        if self.history.len() > self.history_limit {
+            let excess = self.history.len() - self.history_limit;
+            self.history.drain(..excess);
+        }
+    }
+
+    /// Set the function used to complete text when `Tab` is pressed.
+    ///
+    /// `completer` is given the text before the cursor and returns
+    /// candidates to replace it with.  If there are multiple candidates,
+    /// their common prefix is inserted first and pressing `Tab` again
+    /// cycles through them.
+    pub fn set_completer<F: FnMut(&str) -> Vec<String> + Send + 'static>(&mut self, completer: F) {
+        self.completer = Some(Box::new(completer));
+    }
+
+    /// Remove the completion function.
+    pub fn clear_completer(&mut self) {
+        self.completer = None;
+    }
+
+    /// Test if characters typed overwrite the text after the cursor
+    /// rather than being inserted.
+    pub fn overwrite(&self) -> bool {
+        self.overwrite
+    }
+
+    /// Set if characters typed overwrite the text after the cursor
+    /// rather than being inserted.  This is toggled by `Insert`.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+    }
+
+    /// Read a line of text in `window`.
+    ///
+    /// The line is displayed in the `width` columns of `window` starting
+    /// at `start`.  Text that doesn't fit is scrolled horizontally.
+    /// Keys are read via [`Window::read_key`].  Reads block while the
+    /// line is edited; the timeout of `window` (see
+    /// [`Window::set_timeout`]) is restored afterwards.
+    ///
+    /// Returns `None` if editing was cancelled.  Accepted lines are added
+    /// to the history.  Fails if a key can't be read.
+    ///
+    /// [`Window::read_key`]: struct.Window.html#method.read_key
+    /// [`Window::set_timeout`]: struct.Window.html#method.set_timeout
+    pub fn read_line<P: Into<Point>>(
+        &mut self,
+        window: &mut Window,
+        start: P,
+        width: i32,
+    ) -> Result<Option<String>, Error> {
+        let start = start.into();
+        if width <= 0 {
+            return Err(Error::new(ErrorKind::InvalidArgument, "getstr")
+                .with_arguments(format!("{:?}, {}", start, width)));
+        }
+        let delay = sys::get_delay(window.as_ptr());
+        sys::wtimeout(window.as_ptr(), -1);
+        let result = self.edit_line(window, start, width as usize);
+        sys::wtimeout(window.as_ptr(), delay);
+        result
+    }
+
+    fn edit_line(
+        &mut self,
+        window: &mut Window,
+        start: Point,
+        width: usize,
+    ) -> Result<Option<String>, Error> {
+        let mut line = Line::new(self.history.len());
+        loop {
+            line.scroll_to_cursor(text_width(window, start, width));
+            draw(window, start, width, &line)?;
+            let key = match window.read_key() {
+                Some(key) => key,
+                None => return Err(Error::new(ErrorKind::Failed, "getch")),
+            };
+            match self.edit(&mut line, key) {
+                Status::Editing => {}
+                Status::Accepted => {
+                    let text = line.text();
+                    self.add_history(text.clone());
+                    return Ok(Some(text));
+                }
+                Status::Cancelled => return Ok(None),
+            }
+        }
+    }
+
+    fn edit(&mut self, line: &mut Line, key: KeyEvent) -> Status {
+        let completions = line.completions.take();
+        let ctrl = |c| KeyEvent::new(Key::Char(c), Modifiers::CTRL);
+        let alt = |c| KeyEvent::new(Key::Char(c), Modifiers::ALT);
+        match key {
+            k if k == Key::Enter.into() => return Status::Accepted,
+            k if k == Key::Escape.into() || k == ctrl('g') || k == ctrl('c') => {
+                return Status::Cancelled
+            }
+            k if k == Key::Left.into() || k == ctrl('b') => {
+                line.cursor = line.cursor.saturating_sub(1);
+            }
+            k if k == Key::Right.into() || k == ctrl('f') => {
+                line.cursor = (line.cursor + 1).min(line.chars.len());
+            }
+            k if k == Key::Home.into() || k == ctrl('a') => line.cursor = 0,
+            k if k == Key::End.into() || k == ctrl('e') => line.cursor = line.chars.len(),
+            k if k == alt('b') || k == KeyEvent::new(Key::Left, Modifiers::CTRL) => {
+                line.cursor = line.word_start();
+            }
+            k if k == alt('f') || k == KeyEvent::new(Key::Right, Modifiers::CTRL) => {
+                line.cursor = line.word_end();
+            }
+            k if k == Key::Backspace.into() && line.cursor > 0 => {
+                line.cursor -= 1;
+                line.chars.remove(line.cursor);
+            }
+            k if (k == Key::Delete.into() || k == ctrl('d')) && line.cursor < line.chars.len() => {
+                line.chars.remove(line.cursor);
+            }
+            k if k == ctrl('k') => {
+                self.kill_buffer = line.chars.split_off(line.cursor);
+            }
+            k if k == ctrl('u') => {
+                self.kill_buffer = line.chars.drain(..line.cursor).collect();
+                line.cursor = 0;
+            }
+            k if k == ctrl('w') || k == KeyEvent::new(Key::Backspace, Modifiers::ALT) => {
+                let start = line.word_start();
+                self.kill_buffer = line.chars.drain(start..line.cursor).collect();
+                line.cursor = start;
+            }
+            k if k == alt('d') => {
+                let end = line.word_end();
+                self.kill_buffer = line.chars.drain(line.cursor..end).collect();
+            }
+            k if k == ctrl('y') => {
+                let cursor = line.cursor;
+                line.chars
+                    .splice(cursor..cursor, self.kill_buffer.iter().cloned());
+                line.cursor += self.kill_buffer.len();
+            }
+            k if k == Key::Insert.into() => self.overwrite = !self.overwrite,
+            k if k == Key::Up.into() || k == ctrl('p') => self.history_move(line, -1),
+            k if k == Key::Down.into() || k == ctrl('n') => self.history_move(line, 1),
+            k if k == Key::Tab.into() => self.complete(line, completions),
+            KeyEvent {
+                key: Key::Char(c),
+                modifiers,
+            } if !modifiers.ctrl && !modifiers.alt && accepts(c) => {
+                if self.overwrite && line.cursor < line.chars.len() {
+                    line.chars[line.cursor] = c;
+                } else {
+                    line.chars.insert(line.cursor, c);
+                }
+                line.cursor += 1;
+            }
+            _ => {}
+        }
+        Status::Editing
+    }
+
+    fn history_move(&mut self, line: &mut Line, direction: isize) {
+        let index = line.history_index as isize + direction;
+        if index < 0 || index > self.history.len() as isize {
+            return;
+        }
+        if line.history_index == self.history.len() {
+            line.saved = line.chars.clone();
+        }
+        line.history_index = index as usize;
+        let chars = match self.history.get(line.history_index) {
+            Some(entry) => entry.chars().collect(),
+            None => line.saved.clone(),
+        };
+        line.set_text(chars);
+    }
+
+    fn complete(&mut self, line: &mut Line, completions: Option<(Vec<String>, Option<usize>)>) {
+        if let Some((candidates, index)) = completions {
+            let index = index.map_or(0, |i| (i + 1) % candidates.len());
+            line.replace_prefix(&candidates[index]);
+            line.completions = Some((candidates, Some(index)));
+            return;
+        }
+        let completer = match self.completer {
+            Some(ref mut completer) => completer,
+            None => return,
+        };
+        let prefix: String = line.chars[..line.cursor].iter().collect();
+        let candidates = completer(&prefix);
+        match candidates.len() {
+            0 => {}
+            1 => line.replace_prefix(&candidates[0]),
+            _ => {
+                line.replace_prefix(&common_prefix(&candidates));
+                line.completions = Some((candidates, None));
+            }
+        }
+    }
+}
+
+/// Test if `c` can be entered into a `LineEditor`.
+fn accepts(c: char) -> bool {
+    !c.is_control() && (cfg!(feature = "wide") || c.is_ascii())
+}
+
+fn common_prefix(strings: &[String]) -> String {
+    let mut prefix: &str = &strings[0];
+    for s in &strings[1..] {
+        let length = prefix
+            .char_indices()
+            .zip(s.chars())
+            .find(|&((_, a), b)| a != b)
+            .map_or(prefix.len().min(s.len()), |((i, _), _)| i);
+        prefix = &prefix[..length];
+    }
+    prefix.to_string()
+}
+
+/// Get the number of columns text is drawn in when editing in the
+/// `width` columns of `window` starting at `start`.
+///
+/// Writing the bottom right corner of a window fails, so it is left for
+/// the cursor and cleared instead.
+fn text_width(window: &Window, start: Point, width: usize) -> usize {
+    let size = window.size();
+    if start.y == size.rows - 1 && start.x + width as i32 >= size.columns {
+        width - 1
+    } else {
+        width
+    }
+}
+
+// `iter::repeat_n` needs a newer Rust than this crate supports.
+#[allow(clippy::manual_repeat_n)]
+fn draw(window: &mut Window, start: Point, width: usize, line: &Line) -> Result<(), Error> {
+    let text_width = text_width(window, start, width);
+
+    let mut visible = String::new();
+    let mut used = 0;
+    for &c in &line.chars[line.scroll..] {
+        if used + char_width(c) > text_width {
+            break;
+        }
+        visible.push(c);
+        used += char_width(c);
+    }
+    visible.extend(std::iter::repeat(' ').take(text_width - used));
+    window.move_put_str(start, visible)?;
+    if text_width < width {
+        window.clear_to_end_of_line()?;
+    }
+    window.move_to(Point {
+        y: start.y,
+        x: start.x + line.columns(line.scroll..line.cursor) as i32,
+    })?;
+    window.refresh()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_keys(editor: &mut LineEditor, line: &mut Line, keys: &str) {
+        for key in keys.split_whitespace() {
+            editor.edit(line, key.parse().unwrap());
+        }
+    }
+
+    fn type_text(editor: &mut LineEditor, line: &mut Line, text: &str) {
+        for c in text.chars() {
+            editor.edit(line, KeyEvent::from_char(c));
+        }
+    }
+
+    #[test]
+    fn kill_and_yank() {
+        let mut editor = LineEditor::new();
+        let mut line = Line::new(0);
+        type_text(&mut editor, &mut line, "hello big world");
+        type_keys(&mut editor, &mut line, "M-b C-w");
+        assert_eq!(line.text(), "hello world");
+        type_keys(&mut editor, &mut line, "C-e C-y");
+        assert_eq!(line.text(), "hello worldbig ");
+        type_keys(&mut editor, &mut line, "C-a C-k");
+        assert_eq!(line.text(), "");
+        type_keys(&mut editor, &mut line, "C-y C-a Insert");
+        type_text(&mut editor, &mut line, "J");
+        assert_eq!(line.text(), "Jello worldbig ");
+    }
+
+    #[test]
+    fn history() {
+        let mut editor = LineEditor::new();
+        editor.add_history("first");
+        editor.add_history("second");
+        editor.add_history("second");
+        assert_eq!(editor.history(), &["first", "second"]);
+        let mut line = Line::new(editor.history().len());
+        type_text(&mut editor, &mut line, "new");
+        type_keys(&mut editor, &mut line, "Up Up Up");
+        assert_eq!(line.text(), "first");
+        type_keys(&mut editor, &mut line, "Down Down");
+        assert_eq!(line.text(), "new");
+        editor.set_history_limit(1);
+        assert_eq!(editor.history(), &["second"]);
+    }
+
+    #[test]
+    fn completion() {
+        let mut editor = LineEditor::new();
+        editor.set_completer(|prefix| {
+            ["print", "println", "exit"]
+                .iter()
+                .filter(|c| c.starts_with(prefix))
+                .map(|c| c.to_string())
+                .collect()
+        });
+        let mut line = Line::new(0);
+        type_text(&mut editor, &mut line, "e");
+        type_keys(&mut editor, &mut line, "Tab");
+        assert_eq!(line.text(), "exit");
+        type_keys(&mut editor, &mut line, "C-u");
+        type_text(&mut editor, &mut line, "p");
+        type_keys(&mut editor, &mut line, "Tab");
+        assert_eq!(line.text(), "print");
+        type_keys(&mut editor, &mut line, "Tab Tab");
+        assert_eq!(line.text(), "println");
+    }
+
+    #[test]
+    fn scrolling() {
+        let mut line = Line::new(0);
+        line.set_text("abcdefghij".chars().collect());
+        line.scroll_to_cursor(4);
+        assert_eq!(line.scroll, 7);
+        line.cursor = 2;
+        line.scroll_to_cursor(4);
+        assert_eq!(line.scroll, 2);
+    }
+
+    #[cfg(feature = "wide")]
+    #[test]
+    fn scrolling_wide_characters() {
+        let mut line = Line::new(0);
+        line.set_text("日本語です".chars().collect());
+        // The blank after the text needs a column of its own.
+        line.scroll_to_cursor(6);
+        assert_eq!(line.scroll, 3);
+        line.cursor = 1;
+        line.scroll_to_cursor(6);
+        assert_eq!(line.scroll, 1);
+        line.cursor = 3;
+        line.scroll_to_cursor(6);
+        assert_eq!(line.scroll, 1);
+        assert_eq!(line.columns(line.scroll..line.cursor), 4);
+    }
+}
@@ -0,0 +1,234 @@
+use general::*;
+use key::Modifiers;
+use point::*;
+use window::Window;
+
+/// A button on the mouse.
+///
+/// The scroll wheel is reported as [`MouseAction::ScrollUp`] and
+/// [`MouseAction::ScrollDown`] rather than as buttons.
+///
+/// [`MouseAction::ScrollUp`]: enum.MouseAction.html#variant.ScrollUp
+/// [`MouseAction::ScrollDown`]: enum.MouseAction.html#variant.ScrollDown
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// Button 1.
+    Left,
+    /// Button 2.
+    Middle,
+    /// Button 3.
+    Right,
+}
+
+/// What happened in a [`MouseEvent`].
+///
+/// [`MouseEvent`]: struct.MouseEvent.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseAction {
+    Press(MouseButton),
+    Release(MouseButton),
+    Click(MouseButton),
+    DoubleClick(MouseButton),
+    TripleClick(MouseButton),
+    ScrollUp,
+    ScrollDown,
+    /// The mouse moved.  This is only reported if
+    /// `REPORT_MOUSE_POSITION` is in the mask given to
+    /// [`Curses::set_mouse_mask`].
+    ///
+    /// [`Curses::set_mouse_mask`]: struct.Curses.html#method.set_mouse_mask
+    Move,
+}
+
+/// A mouse event read via [`Curses::mouse_read`].
+///
+/// `point` is relative to the screen.  Use [`route`] to find the
+/// `Window` the event occurred in.
+///
+/// [`Curses::mouse_read`]: struct.Curses.html#method.mouse_read
+/// [`route`]: struct.MouseEvent.html#method.route
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub action: MouseAction,
+    pub modifiers: Modifiers,
+    pub point: Point,
+}
+
+/// Whether curses uses the version 1 mouse ABI (ncurses 5).
+///
+/// Version 1 only has room for four buttons: the bits that would
+/// represent button 5 are used by the modifiers instead, and scrolling
+/// down is reported as `REPORT_MOUSE_POSITION`.
+const LEGACY_ABI: bool = cfg!(unix) && REPORT_MOUSE_POSITION == 0x0800_0000;
+
+const BUTTONS: [(MouseButton, [MouseMask; 5]); 3] = [
+    (
+        MouseButton::Left,
+        [
+            BUTTON1_PRESSED,
+            BUTTON1_RELEASED,
+            BUTTON1_CLICKED,
+            BUTTON1_DOUBLE_CLICKED,
+            BUTTON1_TRIPLE_CLICKED,
+        ],
+    ),
+    (
+        MouseButton::Middle,
+        [
+            BUTTON2_PRESSED,
+            BUTTON2_RELEASED,
+            BUTTON2_CLICKED,
+            BUTTON2_DOUBLE_CLICKED,
+            BUTTON2_TRIPLE_CLICKED,
+        ],
+    ),
+    (
+        MouseButton::Right,
+        [
+            BUTTON3_PRESSED,
+            BUTTON3_RELEASED,
+            BUTTON3_CLICKED,
+            BUTTON3_DOUBLE_CLICKED,
+            BUTTON3_TRIPLE_CLICKED,
+        ],
+    ),
+];
+
+impl MouseEvent {
+    /// Decode a [`RawMouseEvent`].
+    ///
+    /// `reporting_position` is whether `REPORT_MOUSE_POSITION` is in the
+    /// current mouse mask.  It is needed to distinguish moving the mouse
+    /// from scrolling down under the version 1 mouse ABI.
+    ///
+    /// Returns `None` if `raw` doesn't contain a recognized event.
+    ///
+    /// [`RawMouseEvent`]: type.RawMouseEvent.html
+    pub(crate) fn from_raw(raw: &RawMouseEvent, reporting_position: bool) -> Option<Self> {
+        let action = MouseEvent::decode_action(raw.bstate, reporting_position)?;
+        Some(MouseEvent {
+            action,
+            modifiers: Modifiers {
+                ctrl: raw.bstate & BUTTON_CTRL != 0,
+                alt: raw.bstate & BUTTON_ALT != 0,
+                shift: raw.bstate & BUTTON_SHIFT != 0,
+            },
+            point: Point { y: raw.y, x: raw.x },
+        })
+    }
+
+    fn decode_action(bstate: MouseMask, reporting_position: bool) -> Option<MouseAction> {
+        if LEGACY_ABI && !reporting_position && bstate & REPORT_MOUSE_POSITION != 0 {
+            return Some(MouseAction::ScrollDown);
+        }
+        for &(button, masks) in &BUTTONS {
+            let actions = [
+                MouseAction::Press(button),
+                MouseAction::Release(button),
+                MouseAction::Click(button),
+                MouseAction::DoubleClick(button),
+                MouseAction::TripleClick(button),
+            ];
+            for (mask, action) in masks.iter().zip(&actions) {
+                if bstate & mask != 0 {
+                    return Some(*action);
+                }
+            }
+        }
+        if bstate & BUTTON4_PRESSED != 0 {
+            Some(MouseAction::ScrollUp)
+        } else if !LEGACY_ABI && bstate & BUTTON5_PRESSED != 0 {
+            Some(MouseAction::ScrollDown)
+        } else if bstate & REPORT_MOUSE_POSITION != 0 {
+            Some(MouseAction::Move)
+        } else {
+            None
+        }
+    }
+
+    /// Find the topmost `Window` containing this event.
+    ///
+    /// `windows` are ordered from bottom to top, so later `Window`s are
+    /// checked first.  Returns the index of the `Window` in `windows`
+    /// along with this event translated to be relative to that `Window`.
+    ///
+    /// This uses [`Window::encloses`] and [`Window::screen_to_window`].
+    ///
+    /// [`Window::encloses`]: struct.Window.html#method.encloses
+    /// [`Window::screen_to_window`]: struct.Window.html#method.screen_to_window
+    pub fn route(&self, windows: &[&Window]) -> Option<(usize, MouseEvent)> {
+        windows
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|&(_, window)| window.encloses(self.point))
+            .map(|(index, window)| {
+                (
+                    index,
+                    MouseEvent {
+                        point: window.screen_to_window(self.point),
+                        ..*self
+                    },
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bstate: MouseMask) -> Option<MouseAction> {
+        MouseEvent::decode_action(bstate, false)
+    }
+
+    #[test]
+    fn buttons() {
+        assert_eq!(
+            decode(BUTTON1_PRESSED),
+            Some(MouseAction::Press(MouseButton::Left))
+        );
+        assert_eq!(
+            decode(BUTTON2_CLICKED),
+            Some(MouseAction::Click(MouseButton::Middle))
+        );
+        assert_eq!(
+            decode(BUTTON3_TRIPLE_CLICKED | BUTTON_CTRL),
+            Some(MouseAction::TripleClick(MouseButton::Right))
+        );
+        assert_eq!(decode(0), None);
+    }
+
+    #[test]
+    fn scrolling() {
+        assert_eq!(decode(BUTTON4_PRESSED), Some(MouseAction::ScrollUp));
+        if LEGACY_ABI {
+            assert_eq!(decode(REPORT_MOUSE_POSITION), Some(MouseAction::ScrollDown));
+        } else {
+            assert_eq!(decode(BUTTON5_PRESSED), Some(MouseAction::ScrollDown));
+        }
+        assert_eq!(
+            MouseEvent::decode_action(REPORT_MOUSE_POSITION, true),
+            Some(MouseAction::Move)
+        );
+    }
+
+    #[test]
+    fn modifiers() {
+        let raw = RawMouseEvent {
+            id: 0,
+            x: 3,
+            y: 4,
+            z: 0,
+            bstate: BUTTON1_RELEASED | BUTTON_SHIFT | BUTTON_ALT,
+        };
+        assert_eq!(
+            MouseEvent::from_raw(&raw, false),
+            Some(MouseEvent {
+                action: MouseAction::Release(MouseButton::Left),
+                modifiers: Modifiers::SHIFT.union(Modifiers::ALT),
+                point: Point { y: 4, x: 3 },
+            })
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use error::*;
+use general::*;
+use point::*;
+use rect::Rect;
+use std::ops::{Deref, DerefMut};
+use sys;
+use window::Window;
+
+/// A curses pad.
+///
+/// A pad is a [`Window`] that is not associated with a part of the
+/// screen.  Thus it may be larger than the terminal.  Only a region of
+/// the `Pad` is shown on the screen at a time, see [`refresh_region`].
+///
+/// All of the drawing methods of [`Window`] are available through
+/// `Deref`.  [`Window::refresh`] and [`Window::refresh_virtual_screen`]
+/// will fail on a `Pad`; use [`refresh_region`] and
+/// [`refresh_virtual_screen_region`] instead.
+///
+/// To create a `Pad`, see [`Curses::create_pad`].
+///
+/// [`Window`]: struct.Window.html
+/// [`Window::refresh`]: struct.Window.html#method.refresh
+/// [`Window::refresh_virtual_screen`]: struct.Window.html#method.refresh_virtual_screen
+/// [`refresh_region`]: struct.Pad.html#method.refresh_region
+/// [`refresh_virtual_screen_region`]: struct.Pad.html#method.refresh_virtual_screen_region
+/// [`Curses::create_pad`]: struct.Curses.html#method.create_pad
+pub struct Pad {
+    window: Window,
+}
+
+impl Pad {
+    pub(crate) fn new(window: Window) -> Self {
+        Pad { window }
+    }
+
+    /// Create a new pad inside of this `Pad`.
+    ///
+    /// `point` is relative to the start of this `Pad`.
+    ///
+    /// This corresponds to `subpad`.  Note that the arguments have been
+    /// reordered to be more consistent with other functions.
+    pub fn create_sub_pad<P: Into<Point>, D: Into<Dimension>>(
+        &self,
+        point: P,
+        size: D,
+    ) -> Result<Pad, Error> {
+        let p = point.into();
+        let d = size.into();
+        match self.window.raw().subpad(d.rows, d.columns, p.y, p.x) {
+            Some(w) => Ok(Pad::new(Window::with_parent(w, &self.window))),
+            None => {
+                let size = self.size();
+                let kind = if p.y < 0
+                    || p.x < 0
+                    || p.y + d.rows > size.rows
+                    || p.x + d.columns > size.columns
+                {
+                    ErrorKind::OutOfBounds
+                } else {
+                    ErrorKind::Failed
+                };
+                Err(Error::new(kind, "subpad").with_arguments(format!("{:?}, {:?}", p, d)))
+            }
+        }
+    }
+
+    /// Copy a region of this `Pad` to the physical screen.
+    ///
+    /// The region of the `Pad` starting at `pad_origin` is displayed in
+    /// the `screen` rectangle.  Nothing is copied if `screen` is empty.
+    ///
+    /// This corresponds to `prefresh`.
+    pub fn refresh_region<P: Into<Point>, R: Into<Rect>>(
+        &mut self,
+        pad_origin: P,
+        screen: R,
+    ) -> Result<(), Error> {
+        self.refresh_with("prefresh", sys::prefresh, pad_origin.into(), screen.into())
+    }
+    /// Copy a region of this `Pad` to the virtual screen.
+    ///
+    /// This is the same as [`refresh_region`] except that the physical
+    /// screen isn't updated until [`Curses::update`] is called.
+    ///
+    /// This corresponds to `pnoutrefresh`.
+    ///
+    /// [`refresh_region`]: struct.Pad.html#method.refresh_region
+    /// [`Curses::update`]: struct.Curses.html#method.update
+    pub fn refresh_virtual_screen_region<P: Into<Point>, R: Into<Rect>>(
+        &mut self,
+        pad_origin: P,
+        screen: R,
+    ) -> Result<(), Error> {
+        self.refresh_with(
+            "pnoutrefresh",
+            sys::pnoutrefresh,
+            pad_origin.into(),
+            screen.into(),
+        )
+    }
+
+    fn refresh_with(
+        &mut self,
+        function: &'static str,
+        refresh: fn(sys::WindowPointer, i32, i32, i32, i32, i32, i32) -> i32,
+        pad_origin: Point,
+        screen: Rect,
+    ) -> Result<(), Error> {
+        if screen.is_empty() {
+            return Ok(());
+        }
+        // The end given to curses is inclusive.
+        let end = screen.end()
+            - Dimension {
+                rows: 1,
+                columns: 1,
+            };
+        let r = refresh(
+            self.window.as_ptr(),
+            pad_origin.y,
+            pad_origin.x,
+            screen.origin.y,
+            screen.origin.x,
+            end.y,
+            end.x,
+        );
+        check(function, r).map_err(|e| e.with_arguments(format!("{:?}, {:?}", pad_origin, screen)))
+    }
+
+    /// Delete this `Pad`, allowing for error handling outside of
+    /// panicking.
+    pub fn delete_pad(self) -> Result<(), Error> {
+        self.window.delete_window()
+    }
+}
+
+impl Deref for Pad {
+    type Target = Window;
+
+    fn deref(&self) -> &Window {
+        &self.window
+    }
+}
+
+impl DerefMut for Pad {
+    fn deref_mut(&mut self) -> &mut Window {
+        &mut self.window
+    }
+}
+
+/// Duplicate this `Pad`.
+///
+/// This corresponds to `dupwin`.
+impl Clone for Pad {
+    fn clone(&self) -> Self {
+        Pad {
+            window: self.window.clone(),
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use std::ops::{Add, Sub};
+
 /// A two-dimensional point
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Point {
     pub y: i32,
     pub x: i32,
@@ -18,6 +20,7 @@ impl From<Point> for (i32, i32) {
 }
 
 /// A two-dimensional dimension
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Dimension {
     pub rows: i32,
     pub columns: i32,
@@ -37,3 +40,45 @@ impl From<Dimension> for (i32, i32) {
         (p.rows, p.columns)
     }
 }
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, other: Point) -> Point {
+        Point {
+            y: self.y + other.y,
+            x: self.x + other.x,
+        }
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, other: Point) -> Point {
+        Point {
+            y: self.y - other.y,
+            x: self.x - other.x,
+        }
+    }
+}
+
+/// Offset a `Point` by `rows` and `columns`.
+impl Add<Dimension> for Point {
+    type Output = Point;
+    fn add(self, size: Dimension) -> Point {
+        Point {
+            y: self.y + size.rows,
+            x: self.x + size.columns,
+        }
+    }
+}
+
+/// Offset a `Point` by `-rows` and `-columns`.
+impl Sub<Dimension> for Point {
+    type Output = Point;
+    fn sub(self, size: Dimension) -> Point {
+        Point {
+            y: self.y - size.rows,
+            x: self.x - size.columns,
+        }
+    }
+}
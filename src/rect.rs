@@ -0,0 +1,412 @@
+use point::*;
+
+/// A rectangle of cells.
+///
+/// The rectangle starts at `origin` (its top left cell) and spans
+/// `size`.  A `Rect` with no rows or no columns is empty and contains no
+/// cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub origin: Point,
+    pub size: Dimension,
+}
+
+impl Rect {
+    /// Create a `Rect` at `origin` spanning `size`.
+    pub fn new<P: Into<Point>, D: Into<Dimension>>(origin: P, size: D) -> Self {
+        Rect {
+            origin: origin.into(),
+            size: size.into(),
+        }
+    }
+    /// Create the `Rect` from `start` up to but not including `end`.
+    ///
+    /// If `end` is above or to the left of `start` the `Rect` is empty.
+    pub fn from_points<P1: Into<Point>, P2: Into<Point>>(start: P1, end: P2) -> Self {
+        let start = start.into();
+        let end = end.into();
+        Rect {
+            origin: start,
+            size: Dimension {
+                rows: (end.y - start.y).max(0),
+                columns: (end.x - start.x).max(0),
+            },
+        }
+    }
+
+    /// Get the point just past the bottom right cell.
+    pub fn end(self) -> Point {
+        self.origin + self.size
+    }
+    /// Test if the `Rect` contains no cells.
+    pub fn is_empty(self) -> bool {
+        self.size.rows <= 0 || self.size.columns <= 0
+    }
+    /// Get the number of cells in the `Rect`.
+    pub fn area(self) -> i32 {
+        if self.is_empty() {
+            0
+        } else {
+            self.size.rows * self.size.columns
+        }
+    }
+
+    /// Test if `p` is one of the cells of the `Rect`.
+    pub fn contains<P: Into<Point>>(self, p: P) -> bool {
+        let p = p.into();
+        let end = self.end();
+        p.y >= self.origin.y && p.x >= self.origin.x && p.y < end.y && p.x < end.x
+    }
+    /// Test if every cell of `other` is in this `Rect`.
+    ///
+    /// An empty `other` is contained if its origin is inside of or on the
+    /// edge of this `Rect`.
+    pub fn contains_rect(self, other: Rect) -> bool {
+        let end = self.end();
+        let other_end = other.end();
+        other.origin.y >= self.origin.y
+            && other.origin.x >= self.origin.x
+            && other_end.y <= end.y
+            && other_end.x <= end.x
+            && other.size.rows >= 0
+            && other.size.columns >= 0
+    }
+    /// Get the cells that are in both `Rect`s, or `None` if there are
+    /// none.
+    pub fn intersection(self, other: Rect) -> Option<Rect> {
+        let start = Point {
+            y: self.origin.y.max(other.origin.y),
+            x: self.origin.x.max(other.origin.x),
+        };
+        let end = self.end();
+        let other_end = other.end();
+        let end = Point {
+            y: end.y.min(other_end.y),
+            x: end.x.min(other_end.x),
+        };
+        let rect = Rect::from_points(start, end);
+        if rect.is_empty() {
+            None
+        } else {
+            Some(rect)
+        }
+    }
+    /// Test if the `Rect`s have any cells in common.
+    pub fn intersects(self, other: Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+    /// Get the smallest `Rect` containing both `Rect`s.
+    ///
+    /// Empty `Rect`s are ignored.
+    pub fn union(self, other: Rect) -> Rect {
+        if other.is_empty() {
+            return self;
+        }
+        if self.is_empty() {
+            return other;
+        }
+        let end = self.end();
+        let other_end = other.end();
+        Rect::from_points(
+            Point {
+                y: self.origin.y.min(other.origin.y),
+                x: self.origin.x.min(other.origin.x),
+            },
+            Point {
+                y: end.y.max(other_end.y),
+                x: end.x.max(other_end.x),
+            },
+        )
+    }
+
+    /// Get the cell of the `Rect` closest to `p`.
+    ///
+    /// If the `Rect` is empty this is its origin.
+    pub fn clamp<P: Into<Point>>(self, p: P) -> Point {
+        let p = p.into();
+        if self.is_empty() {
+            return self.origin;
+        }
+        let last = self.end()
+            - Dimension {
+                rows: 1,
+                columns: 1,
+            };
+        Point {
+            y: p.y.max(self.origin.y).min(last.y),
+            x: p.x.max(self.origin.x).min(last.x),
+        }
+    }
+    /// Move `other` so that it is inside of this `Rect`, shrinking it if
+    /// it is too big.
+    pub fn clamp_rect(self, other: Rect) -> Rect {
+        let size = Dimension {
+            rows: other.size.rows.max(0).min(self.size.rows.max(0)),
+            columns: other.size.columns.max(0).min(self.size.columns.max(0)),
+        };
+        let last_origin = self.end() - size;
+        Rect {
+            origin: Point {
+                y: other.origin.y.min(last_origin.y).max(self.origin.y),
+                x: other.origin.x.min(last_origin.x).max(self.origin.x),
+            },
+            size,
+        }
+    }
+
+    /// Move the `Rect` by `offset`.
+    pub fn translate<P: Into<Point>>(self, offset: P) -> Rect {
+        Rect {
+            origin: self.origin + offset.into(),
+            size: self.size,
+        }
+    }
+    /// Shrink the `Rect` by `rows` on the top and bottom and by
+    /// `columns` on the left and right.
+    ///
+    /// The size of the result is never negative.
+    pub fn inset(self, rows: i32, columns: i32) -> Rect {
+        Rect {
+            origin: Point {
+                y: self.origin.y + rows,
+                x: self.origin.x + columns,
+            },
+            size: Dimension {
+                rows: (self.size.rows - 2 * rows).max(0),
+                columns: (self.size.columns - 2 * columns).max(0),
+            },
+        }
+    }
+    /// Grow the `Rect` by `rows` on the top and bottom and by `columns`
+    /// on the left and right.
+    ///
+    /// This is [`inset`] with negated arguments.
+    ///
+    /// [`inset`]: struct.Rect.html#method.inset
+    pub fn outset(self, rows: i32, columns: i32) -> Rect {
+        self.inset(-rows, -columns)
+    }
+
+    /// Split the `Rect` into the first `rows` rows and the rest.
+    ///
+    /// `rows` is clamped to the rows of the `Rect`.
+    pub fn split_rows(self, rows: i32) -> (Rect, Rect) {
+        let rows = rows.max(0).min(self.size.rows.max(0));
+        let top = Rect {
+            origin: self.origin,
+            size: Dimension {
+                rows,
+                columns: self.size.columns,
+            },
+        };
+        let bottom = Rect {
+            origin: Point {
+                y: self.origin.y + rows,
+                x: self.origin.x,
+            },
+            size: Dimension {
+                rows: self.size.rows.max(0) - rows,
+                columns: self.size.columns,
+            },
+        };
+        (top, bottom)
+    }
+    /// Split the `Rect` into the first `columns` columns and the rest.
+    ///
+    /// `columns` is clamped to the columns of the `Rect`.
+    pub fn split_columns(self, columns: i32) -> (Rect, Rect) {
+        let columns = columns.max(0).min(self.size.columns.max(0));
+        let left = Rect {
+            origin: self.origin,
+            size: Dimension {
+                rows: self.size.rows,
+                columns,
+            },
+        };
+        let right = Rect {
+            origin: Point {
+                y: self.origin.y,
+                x: self.origin.x + columns,
+            },
+            size: Dimension {
+                rows: self.size.rows,
+                columns: self.size.columns.max(0) - columns,
+            },
+        };
+        (left, right)
+    }
+
+    /// Get an iterator over the rows of the `Rect`, from top to bottom,
+    /// each as a `Rect` one row tall.
+    pub fn rows(self) -> RectRows {
+        RectRows {
+            rect: self,
+            next: 0,
+        }
+    }
+    /// Get an iterator over the cells of the `Rect`, row by row.
+    pub fn points(self) -> RectPoints {
+        RectPoints {
+            rect: self,
+            next: Point { y: 0, x: 0 },
+        }
+    }
+}
+
+impl From<(Point, Dimension)> for Rect {
+    fn from(v: (Point, Dimension)) -> Self {
+        Rect {
+            origin: v.0,
+            size: v.1,
+        }
+    }
+}
+
+impl From<Rect> for (Point, Dimension) {
+    fn from(rect: Rect) -> (Point, Dimension) {
+        (rect.origin, rect.size)
+    }
+}
+
+/// An iterator over the rows of a [`Rect`].
+///
+/// To create a `RectRows`, see [`Rect::rows`].
+///
+/// [`Rect`]: struct.Rect.html
+/// [`Rect::rows`]: struct.Rect.html#method.rows
+#[derive(Clone, Debug)]
+pub struct RectRows {
+    rect: Rect,
+    next: i32,
+}
+
+impl Iterator for RectRows {
+    type Item = Rect;
+
+    fn next(&mut self) -> Option<Rect> {
+        if self.rect.is_empty() || self.next >= self.rect.size.rows {
+            return None;
+        }
+        let row = Rect {
+            origin: Point {
+                y: self.rect.origin.y + self.next,
+                x: self.rect.origin.x,
+            },
+            size: Dimension {
+                rows: 1,
+                columns: self.rect.size.columns,
+            },
+        };
+        self.next += 1;
+        Some(row)
+    }
+}
+
+/// An iterator over the cells of a [`Rect`], row by row.
+///
+/// To create a `RectPoints`, see [`Rect::points`].
+///
+/// [`Rect`]: struct.Rect.html
+/// [`Rect::points`]: struct.Rect.html#method.points
+#[derive(Clone, Debug)]
+pub struct RectPoints {
+    rect: Rect,
+    next: Point,
+}
+
+impl Iterator for RectPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        if self.rect.is_empty() || self.next.y >= self.rect.size.rows {
+            return None;
+        }
+        let p = self.rect.origin + self.next;
+        self.next.x += 1;
+        if self.next.x == self.rect.size.columns {
+            self.next.x = 0;
+            self.next.y += 1;
+        }
+        Some(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(y: i32, x: i32, rows: i32, columns: i32) -> Rect {
+        Rect::new((y, x), (rows, columns))
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let p = Point { y: 3, x: 4 };
+        assert_eq!(p + Point { y: 1, x: 2 }, Point { y: 4, x: 6 });
+        assert_eq!(p - Point { y: 1, x: 2 }, Point { y: 2, x: 2 });
+        assert_eq!(p + Dimension::from((2, 3)), Point { y: 5, x: 7 });
+        assert_eq!(p - Dimension::from((2, 3)), Point { y: 1, x: 1 });
+    }
+
+    #[test]
+    fn containment() {
+        let r = rect(1, 2, 3, 4);
+        assert_eq!(r.end(), Point { y: 4, x: 6 });
+        assert_eq!(r.area(), 12);
+        assert!(r.contains((1, 2)));
+        assert!(r.contains((3, 5)));
+        assert!(!r.contains((4, 5)));
+        assert!(!r.contains((3, 6)));
+        assert!(r.contains_rect(rect(2, 3, 2, 3)));
+        assert!(!r.contains_rect(rect(2, 3, 3, 3)));
+        assert!(rect(0, 0, 0, 5).is_empty());
+        assert!(!rect(0, 0, 0, 5).contains((0, 0)));
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = rect(0, 0, 4, 4);
+        let b = rect(2, 3, 4, 4);
+        assert_eq!(a.intersection(b), Some(rect(2, 3, 2, 1)));
+        assert!(a.intersects(b));
+        assert_eq!(a.intersection(rect(4, 0, 1, 1)), None);
+        assert_eq!(a.union(b), rect(0, 0, 6, 7));
+        assert_eq!(a.union(rect(10, 10, 0, 0)), a);
+    }
+
+    #[test]
+    fn clamping() {
+        let r = rect(1, 1, 3, 3);
+        assert_eq!(r.clamp((0, 2)), Point { y: 1, x: 2 });
+        assert_eq!(r.clamp((9, 9)), Point { y: 3, x: 3 });
+        assert_eq!(r.clamp_rect(rect(2, 3, 2, 2)), rect(2, 2, 2, 2));
+        assert_eq!(r.clamp_rect(rect(-5, 0, 5, 1)), rect(1, 1, 3, 1));
+    }
+
+    #[test]
+    fn inset_and_outset() {
+        let r = rect(1, 1, 5, 6);
+        assert_eq!(r.inset(1, 2), rect(2, 3, 3, 2));
+        assert_eq!(r.inset(3, 0), rect(4, 1, 0, 6));
+        assert_eq!(r.outset(1, 1), rect(0, 0, 7, 8));
+        assert_eq!(r.translate((1, -1)), rect(2, 0, 5, 6));
+    }
+
+    #[test]
+    fn splitting() {
+        let r = rect(1, 1, 5, 6);
+        assert_eq!(r.split_rows(2), (rect(1, 1, 2, 6), rect(3, 1, 3, 6)));
+        assert_eq!(r.split_rows(9), (r, rect(6, 1, 0, 6)));
+        assert_eq!(r.split_columns(4), (rect(1, 1, 5, 4), rect(1, 5, 5, 2)));
+    }
+
+    #[test]
+    fn iteration() {
+        let r = rect(1, 2, 2, 2);
+        let rows: Vec<Rect> = r.rows().collect();
+        assert_eq!(rows, [rect(1, 2, 1, 2), rect(2, 2, 1, 2)]);
+        let points: Vec<(i32, i32)> = r.points().map(Into::into).collect();
+        assert_eq!(points, [(1, 2), (1, 3), (2, 2), (2, 3)]);
+        assert_eq!(rect(0, 0, 3, 0).points().count(), 0);
+    }
+}
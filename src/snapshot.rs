@@ -0,0 +1,303 @@
+use backend::Backend;
+use color::ColorPairId;
+use general::*;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// The environment variable that makes [`assert_snapshot`] store the
+/// rendered screen instead of comparing against it.
+///
+/// [`assert_snapshot`]: fn.assert_snapshot.html
+pub const UPDATE_SNAPSHOTS: &str = "PANCURSES_RESULT_UPDATE_SNAPSHOTS";
+
+/// The named attributes, in the order they are written in a
+/// `Snapshot`.
+const ATTRIBUTE_NAMES: &[(Chtype, &str)] = &[
+    (A_ALTCHARSET, "A_ALTCHARSET"),
+    (A_BLINK, "A_BLINK"),
+    (A_BOLD, "A_BOLD"),
+    (A_DIM, "A_DIM"),
+    (A_INVIS, "A_INVIS"),
+    (A_ITALIC, "A_ITALIC"),
+    (A_REVERSE, "A_REVERSE"),
+    (A_STANDOUT, "A_STANDOUT"),
+    (A_UNDERLINE, "A_UNDERLINE"),
+];
+
+/// The character rendered for a cell that can't be read.
+const UNREADABLE: char = '?';
+
+/// The keys of the style layer of a `Snapshot`, in the order they are
+/// handed out.
+const STYLE_KEYS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The contents of a [`Backend`] rendered as stable text, for
+/// comparing against a stored snapshot.
+///
+/// The text has a layer of characters and, if any cell has attributes
+/// or a color pair, a layer of styles.  Each line of a layer is
+/// surrounded by `|` so trailing blanks are visible.  In the style
+/// layer, cells without a style are `.` and every other style is given
+/// a key that is explained below the layer:
+///
+/// ```text
+/// |hello  |
+/// |  !    |
+/// styles:
+/// |aaaaa..|
+/// |..b....|
+/// a: A_BOLD
+/// b: A_UNDERLINE, pair 2
+/// ```
+///
+/// Cells that can't be read are rendered as `?` without a style.
+///
+/// To compare against a file, see [`assert_snapshot`].
+///
+/// [`Backend`]: trait.Backend.html
+/// [`assert_snapshot`]: fn.assert_snapshot.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    text: String,
+}
+
+impl Snapshot {
+    /// Render the contents of `backend`.
+    pub fn new<B: Backend + ?Sized>(backend: &B) -> Self {
+        let size = backend.size();
+        let mut text = String::new();
+        let mut styles = Vec::new();
+        let mut style_lines = Vec::new();
+        for y in 0..size.rows {
+            let mut style_line = String::new();
+            text.push('|');
+            for x in 0..size.columns {
+                let cell = match backend.cell_at((y, x).into()) {
+                    Ok(cell) => cell,
+                    // Keep the following cells in their columns.
+                    Err(_) => {
+                        text.push(UNREADABLE);
+                        style_line.push('.');
+                        continue;
+                    }
+                };
+                text.push(cell.character);
+                let style = (cell.attributes, cell.color_pair);
+                if style == (0, ColorPairId::default()) {
+                    style_line.push('.');
+                    continue;
+                }
+                let index = match styles.iter().position(|s| *s == style) {
+                    Some(index) => index,
+                    None => {
+                        styles.push(style);
+                        styles.len() - 1
+                    }
+                };
+                style_line.push(STYLE_KEYS.chars().nth(index).unwrap_or('?'));
+            }
+            text.push_str("|\n");
+            style_lines.push(style_line);
+        }
+        if !styles.is_empty() {
+            text.push_str("styles:\n");
+            for style_line in style_lines {
+                text.push('|');
+                text.push_str(&style_line);
+                text.push_str("|\n");
+            }
+            for (key, style) in STYLE_KEYS.chars().zip(&styles) {
+                text.push_str(&format!("{}: {}\n", key, describe_style(*style)));
+            }
+        }
+        Snapshot { text }
+    }
+
+    /// Get the rendered text.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Compare against the `expected` text, returning a line by line
+    /// diff if they differ.
+    ///
+    /// Lines only in `expected` are prefixed with `-` and lines only in
+    /// this `Snapshot` are prefixed with `+`.
+    pub fn diff(&self, expected: &str) -> Option<String> {
+        if self.text == expected {
+            None
+        } else {
+            Some(diff_lines(expected, &self.text))
+        }
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+fn describe_style((attributes, color_pair): (Chtype, ColorPairId)) -> String {
+    let mut names = Vec::new();
+    let mut rest = attributes;
+    for &(attribute, name) in ATTRIBUTE_NAMES {
+        if attribute != 0 && rest & attribute == attribute {
+            names.push(name.to_string());
+            rest &= !attribute;
+        }
+    }
+    if rest != 0 {
+        names.push(format!("{:#x}", rest));
+    }
+    if color_pair != ColorPairId::default() {
+        names.push(format!("pair {}", color_pair.number()));
+    }
+    names.join(", ")
+}
+
+/// Diff the lines of `old` and `new` using their longest common
+/// subsequence.
+fn diff_lines(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // `common[i][j]` is the length of the longest common subsequence of
+    // `old[i..]` and `new[j..]`.
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            diff.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// Compare the contents of `backend` against the [`Snapshot`] stored in
+/// the file at `path`.
+///
+/// If the environment variable [`UPDATE_SNAPSHOTS`] is set, the file is
+/// written instead (creating its directory if needed).  Relative paths
+/// are relative to the current directory, which is the package root
+/// under `cargo test`.
+///
+/// # Panics
+///
+/// Panics with a diff if the contents don't match the file, or if the
+/// file can't be read or written.
+///
+/// [`Snapshot`]: struct.Snapshot.html
+/// [`UPDATE_SNAPSHOTS`]: constant.UPDATE_SNAPSHOTS.html
+pub fn assert_snapshot<B: Backend + ?Sized, P: AsRef<Path>>(backend: &B, path: P) {
+    let update = env::var_os(UPDATE_SNAPSHOTS).is_some();
+    if let Err(message) = check_snapshot(&Snapshot::new(backend), path.as_ref(), update) {
+        panic!("{}", message);
+    }
+}
+
+fn check_snapshot(snapshot: &Snapshot, path: &Path, update: bool) -> Result<(), String> {
+    if update {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)
+                .map_err(|e| format!("could not create {}: {}", directory.display(), e))?;
+        }
+        return fs::write(path, snapshot.as_str())
+            .map_err(|e| format!("could not write {}: {}", path.display(), e));
+    }
+    let expected = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "could not read snapshot {}: {}\nset {} to create it; the screen is:\n{}",
+            path.display(),
+            e,
+            UPDATE_SNAPSHOTS,
+            snapshot
+        )
+    })?;
+    match snapshot.diff(&expected) {
+        None => Ok(()),
+        Some(diff) => Err(format!(
+            "the screen doesn't match snapshot {} (set {} to update it):\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS,
+            diff
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use point::Point;
+    use virtual_screen::VirtualScreen;
+
+    #[test]
+    fn plain_screen_has_no_style_layer() {
+        let mut screen = VirtualScreen::new((2, 4));
+        screen.put_str("ab").unwrap();
+        assert_eq!(Snapshot::new(&screen).as_str(), "|ab  |\n|    |\n");
+    }
+
+    #[test]
+    fn styles_are_keyed_in_order_of_appearance() {
+        let mut screen = VirtualScreen::new((2, 4));
+        screen.turn_on_attributes(A_BOLD).unwrap();
+        screen.put_str("ab").unwrap();
+        screen.set_attributes(0).unwrap();
+        screen.set_color(ColorPairId::new(2)).unwrap();
+        screen
+            .move_put_char(Point { y: 1, x: 1 }, 'c' as Chtype | A_UNDERLINE)
+            .unwrap();
+        assert_eq!(
+            Snapshot::new(&screen).as_str(),
+            "|ab  |\n| c  |\nstyles:\n|aa..|\n|.b..|\na: A_BOLD\nb: A_UNDERLINE, pair 2\n"
+        );
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let mut screen = VirtualScreen::new((3, 2));
+        screen.put_str("abcdef").unwrap_err();
+        let snapshot = Snapshot::new(&screen);
+        assert_eq!(snapshot.diff("|ab|\n|cd|\n|ef|\n"), None);
+        assert_eq!(
+            snapshot.diff("|ab|\n|xx|\n|ef|\n").unwrap(),
+            " |ab|\n-|xx|\n+|cd|\n |ef|\n"
+        );
+    }
+
+    #[test]
+    fn check_against_file() {
+        let path = env::temp_dir()
+            .join(format!("pancurses-result-{}", std::process::id()))
+            .join("snapshot.txt");
+        let mut screen = VirtualScreen::new((1, 3));
+        let snapshot = Snapshot::new(&screen);
+        assert!(check_snapshot(&snapshot, &path, false).is_err());
+        assert_eq!(check_snapshot(&snapshot, &path, true), Ok(()));
+        assert_eq!(check_snapshot(&snapshot, &path, false), Ok(()));
+        screen.put_char('x' as Chtype).unwrap();
+        let message = check_snapshot(&Snapshot::new(&screen), &path, false).unwrap_err();
+        assert!(message.contains("-|   |\n+|x  |\n"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
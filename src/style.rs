@@ -0,0 +1,136 @@
+use color::ColorPairId;
+use error::*;
+use general::*;
+use std::ops::{Deref, DerefMut};
+use window::Window;
+
+/// A combination of attributes and a color pair to draw text with.
+///
+/// Styles are applied with [`Window::with_style`] and
+/// [`Window::style_guard`].
+///
+/// [`Window::with_style`]: struct.Window.html#method.with_style
+/// [`Window::style_guard`]: struct.Window.html#method.style_guard
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    /// The attributes to turn on, such as `A_BOLD`.
+    pub attributes: Chtype,
+    /// The color pair to use.  `None` keeps the current color pair.
+    pub color_pair: Option<ColorPairId>,
+}
+
+impl Style {
+    /// Create a `Style` that doesn't change anything.
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    /// Add `attributes` to this `Style`.
+    pub fn with_attributes<T: Into<Chtype>>(self, attributes: T) -> Self {
+        Style {
+            attributes: self.attributes | attributes.into(),
+            ..self
+        }
+    }
+
+    /// Use `color_pair` in this `Style`.
+    pub fn with_color_pair(self, color_pair: ColorPairId) -> Self {
+        Style {
+            color_pair: Some(color_pair),
+            ..self
+        }
+    }
+}
+
+impl From<Chtype> for Style {
+    fn from(attributes: Chtype) -> Self {
+        Style::new().with_attributes(attributes)
+    }
+}
+
+impl From<Attribute> for Style {
+    fn from(attribute: Attribute) -> Self {
+        Style::new().with_attributes(attribute)
+    }
+}
+
+impl From<Attributes> for Style {
+    fn from(attributes: Attributes) -> Self {
+        Style::new().with_attributes(attributes)
+    }
+}
+
+impl From<ColorPairId> for Style {
+    fn from(color_pair: ColorPairId) -> Self {
+        Style::new().with_color_pair(color_pair)
+    }
+}
+
+/// Applies a [`Style`] to a [`Window`] until it is dropped.
+///
+/// The attributes and color pair of the `Window` when the guard was
+/// created are restored on drop, so guards nest correctly.  The `Window`
+/// can be drawn on through the guard via `Deref`.
+///
+/// To create a `StyleGuard`, see [`Window::style_guard`].
+///
+/// [`Style`]: struct.Style.html
+/// [`Window`]: struct.Window.html
+/// [`Window::style_guard`]: struct.Window.html#method.style_guard
+pub struct StyleGuard<'a> {
+    window: &'a mut Window,
+    attributes: Chtype,
+    color_pair: ColorPairId,
+    restored: bool,
+}
+
+impl<'a> StyleGuard<'a> {
+    pub(crate) fn new(window: &'a mut Window, style: Style) -> Result<Self, Error> {
+        let (attributes, color_pair) = window.attributes();
+        let guard = StyleGuard {
+            window,
+            attributes,
+            color_pair,
+            restored: false,
+        };
+        guard.window.turn_on_attributes(style.attributes)?;
+        if let Some(color_pair) = style.color_pair {
+            guard.window.set_color(color_pair)?;
+        }
+        Ok(guard)
+    }
+
+    /// Restore the attributes and color pair, allowing for error
+    /// handling outside of dropping.
+    pub fn restore(mut self) -> Result<(), Error> {
+        self.restore_style()
+    }
+
+    fn restore_style(&mut self) -> Result<(), Error> {
+        self.restored = true;
+        self.window.set_attributes(self.attributes)?;
+        self.window.set_color(self.color_pair)
+    }
+}
+
+impl<'a> Deref for StyleGuard<'a> {
+    type Target = Window;
+
+    fn deref(&self) -> &Window {
+        self.window
+    }
+}
+
+impl<'a> DerefMut for StyleGuard<'a> {
+    fn deref_mut(&mut self) -> &mut Window {
+        self.window
+    }
+}
+
+impl<'a> Drop for StyleGuard<'a> {
+    fn drop(&mut self) {
+        if !self.restored {
+            let _ = self.restore_style();
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use ansi;
+use color::{Color, ColorPairId, ColorPairs};
+use error::*;
+use general::*;
+use std::fmt;
+use std::iter::FromIterator;
+use style::Style;
+use wrap;
+
+/// A piece of text drawn with one [`Style`].
+///
+/// [`Style`]: struct.Style.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    /// Create a `Span` of `text` drawn with `style`.
+    pub fn new<T: Into<String>, S: Into<Style>>(text: T, style: S) -> Self {
+        Span {
+            text: text.into(),
+            style: style.into(),
+        }
+    }
+}
+
+/// Text made of [`Span`]s with different [`Style`]s.
+///
+/// A `StyledText` is built span by span with [`push`] or parsed from
+/// markup with [`parse`], then drawn with [`Window::put_styled`].
+///
+/// [`Span`]: struct.Span.html
+/// [`Style`]: struct.Style.html
+/// [`push`]: struct.StyledText.html#method.push
+/// [`parse`]: struct.StyledText.html#method.parse
+/// [`Window::put_styled`]: struct.Window.html#method.put_styled
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StyledText {
+    spans: Vec<Span>,
+}
+
+impl StyledText {
+    /// Create an empty `StyledText`.
+    pub fn new() -> Self {
+        StyledText::default()
+    }
+
+    /// Parse `markup` into a `StyledText`, allocating the color pairs it
+    /// uses from `pairs`.
+    ///
+    /// Text between `[tags]` and `[/]` is styled by the words in the
+    /// tag, which are applied on top of the tags around it:
+    ///
+    /// * `bold` (or `b`), `dim`, `italic` (or `i`), `underline` (or `u`),
+    ///   `reverse`, `blink` and `standout` turn on attributes.
+    /// * `black`, `red`, `green`, `yellow`, `blue`, `magenta`, `cyan`
+    ///   and `white` set the foreground color.
+    /// * `on` followed by a color sets the background color.
+    ///
+    /// Colors that aren't given default to white on black.  `[/]` closes
+    /// the innermost open tag, and tags left open are closed at the end.
+    /// A literal `[` is written as `[[`; see [`escape_markup`] for
+    /// inserting arbitrary text.
+    ///
+    /// ```no_run
+    /// # use pancurses_result::*;
+    /// # fn f(curses: &mut Curses, file: &str) -> Result<(), Error> {
+    /// let markup = format!("[bold red]Error:[/] file [u]{}[/]", escape_markup(file));
+    /// let text = StyledText::parse(&markup, curses.start_color()?.pairs_mut())?;
+    /// curses.window_mut().put_styled(&text)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// This fails with [`ErrorKind::InvalidArgument`] if `markup` has an
+    /// unknown word, an unterminated tag, or a `[/]` without an open tag.
+    ///
+    /// [`escape_markup`]: fn.escape_markup.html
+    /// [`ErrorKind::InvalidArgument`]: enum.ErrorKind.html#variant.InvalidArgument
+    pub fn parse(markup: &str, pairs: &mut ColorPairs) -> Result<Self, Error> {
+        parse_with(markup, &mut |foreground, background| {
+            pairs.allocate(foreground, background)
+        })
+    }
+
+    /// Parse `text` containing ANSI escape sequences, such as the
+    /// colored output of a compiler, into a `StyledText`.
+    ///
+    /// SGR sequences set the style: bold, dim, italic, underline, blink
+    /// and reverse, the 16 basic colors, the 256 color palette and
+    /// 24-bit colors.  Colors the terminal doesn't have are replaced by
+    /// the closest color it does have.  Color pairs are allocated from
+    /// `color` as needed; when they run out, text is drawn without
+    /// colors.  Other escape sequences are removed.
+    ///
+    /// Foreground or background colors that aren't set are the
+    /// terminal's default colors if [`Color::use_default_colors`] has
+    /// been called and white on black otherwise.
+    ///
+    /// [`Color::use_default_colors`]: struct.Color.html#method.use_default_colors
+    pub fn from_ansi(text: &str, color: &mut Color) -> Result<Self, Error> {
+        let palette = color.palette();
+        let pairs = color.pairs_mut();
+        ansi::parse(text, palette, &mut |foreground, background| {
+            pairs.allocate(foreground, background)
+        })
+    }
+
+    /// Add `text` drawn with `style` to the end.
+    pub fn push<T: Into<String>, S: Into<Style>>(&mut self, text: T, style: S) {
+        let span = Span::new(text, style);
+        if span.text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.style == span.style => last.text.push_str(&span.text),
+            _ => self.spans.push(span),
+        }
+    }
+
+    /// Get the `Span`s making up the text.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Test if there is no text.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Get the number of columns the text takes on the screen.
+    pub fn width(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|span| wrap::display_width(&span.text))
+            .sum()
+    }
+}
+
+/// Displays the text without styles.
+impl fmt::Display for StyledText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for span in &self.spans {
+            f.write_str(&span.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<&'a str> for StyledText {
+    fn from(text: &'a str) -> Self {
+        StyledText::from(Span::new(text, Style::new()))
+    }
+}
+
+impl From<String> for StyledText {
+    fn from(text: String) -> Self {
+        StyledText::from(Span::new(text, Style::new()))
+    }
+}
+
+impl From<Span> for StyledText {
+    fn from(span: Span) -> Self {
+        let mut text = StyledText::new();
+        text.push(span.text, span.style);
+        text
+    }
+}
+
+impl FromIterator<Span> for StyledText {
+    fn from_iter<I: IntoIterator<Item = Span>>(spans: I) -> Self {
+        let mut text = StyledText::new();
+        for span in spans {
+            text.push(span.text, span.style);
+        }
+        text
+    }
+}
+
+/// Escape `text` so that [`StyledText::parse`] reads it literally.
+///
+/// [`StyledText::parse`]: struct.StyledText.html#method.parse
+pub fn escape_markup(text: &str) -> String {
+    text.replace('[', "[[")
+}
+
+/// The state of an open tag.
+#[derive(Clone, Copy)]
+struct Tag {
+    attributes: Chtype,
+    foreground: Option<i16>,
+    background: Option<i16>,
+    style: Style,
+}
+
+fn parse_with(
+    markup: &str,
+    allocate: &mut dyn FnMut(i16, i16) -> Result<ColorPairId, Error>,
+) -> Result<StyledText, Error> {
+    let mut text = StyledText::new();
+    let mut tags = vec![Tag {
+        attributes: 0,
+        foreground: None,
+        background: None,
+        style: Style::new(),
+    }];
+    let mut rest = markup;
+    while let Some(open) = rest.find('[') {
+        let style = tags[tags.len() - 1].style;
+        text.push(&rest[..open], style);
+        rest = &rest[open + 1..];
+        if rest.starts_with('[') {
+            text.push("[", style);
+            rest = &rest[1..];
+            continue;
+        }
+        let close = rest.find(']').ok_or_else(|| markup_error(markup))?;
+        let tag = rest[..close].trim();
+        rest = &rest[close + 1..];
+        if tag == "/" {
+            if tags.len() == 1 {
+                return Err(markup_error("[/]"));
+            }
+            tags.pop();
+        } else {
+            let tag = parse_tag(tag, tags[tags.len() - 1], allocate)?;
+            tags.push(tag);
+        }
+    }
+    text.push(rest, tags[tags.len() - 1].style);
+    Ok(text)
+}
+
+/// Apply the words of `tag` on top of `parent`.
+fn parse_tag(
+    tag: &str,
+    parent: Tag,
+    allocate: &mut dyn FnMut(i16, i16) -> Result<ColorPairId, Error>,
+) -> Result<Tag, Error> {
+    let mut result = parent;
+    let mut words = tag.split_whitespace();
+    let mut empty = true;
+    while let Some(word) = words.next() {
+        empty = false;
+        if word == "on" {
+            let color = words.next().and_then(color);
+            result.background = Some(color.ok_or_else(|| markup_error(tag))?);
+        } else if let Some(color) = color(word) {
+            result.foreground = Some(color);
+        } else {
+            result.attributes |= attribute(word).ok_or_else(|| markup_error(tag))?;
+        }
+    }
+    if empty {
+        return Err(markup_error(tag));
+    }
+
+    result.style = Style::new().with_attributes(result.attributes);
+    if result.foreground.is_some() || result.background.is_some() {
+        let pair = allocate(
+            result.foreground.unwrap_or(COLOR_WHITE),
+            result.background.unwrap_or(COLOR_BLACK),
+        )?;
+        result.style = result.style.with_color_pair(pair);
+    }
+    Ok(result)
+}
+
+fn color(word: &str) -> Option<i16> {
+    match word {
+        "black" => Some(COLOR_BLACK),
+        "red" => Some(COLOR_RED),
+        "green" => Some(COLOR_GREEN),
+        "yellow" => Some(COLOR_YELLOW),
+        "blue" => Some(COLOR_BLUE),
+        "magenta" => Some(COLOR_MAGENTA),
+        "cyan" => Some(COLOR_CYAN),
+        "white" => Some(COLOR_WHITE),
+        _ => None,
+    }
+}
+
+fn attribute(word: &str) -> Option<Chtype> {
+    match word {
+        "bold" | "b" => Some(A_BOLD),
+        "dim" => Some(A_DIM),
+        "italic" | "i" => Some(A_ITALIC),
+        "underline" | "u" => Some(A_UNDERLINE),
+        "reverse" => Some(A_REVERSE),
+        "blink" => Some(A_BLINK),
+        "standout" => Some(A_STANDOUT),
+        _ => None,
+    }
+}
+
+fn markup_error(markup: &str) -> Error {
+    Error::new(ErrorKind::InvalidArgument, "markup").with_arguments(format!("{:?}", markup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse `markup`, numbering color pairs by their colors.
+    fn parse(markup: &str) -> Result<StyledText, Error> {
+        parse_with(markup, &mut |foreground, background| {
+            Ok(ColorPairId::new(foreground * 8 + background + 1))
+        })
+    }
+
+    fn pair(foreground: i16, background: i16) -> ColorPairId {
+        ColorPairId::new(foreground * 8 + background + 1)
+    }
+
+    #[test]
+    fn plain_text() {
+        let text = parse("no tags here").unwrap();
+        assert_eq!(text.spans(), [Span::new("no tags here", Style::new())]);
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn tags() {
+        let text = parse("[bold red]Error:[/] file [u]a.rs[/]").unwrap();
+        let red = Style::new()
+            .with_attributes(A_BOLD)
+            .with_color_pair(pair(COLOR_RED, COLOR_BLACK));
+        assert_eq!(
+            text.spans(),
+            [
+                Span::new("Error:", red),
+                Span::new(" file ", Style::new()),
+                Span::new("a.rs", A_UNDERLINE),
+            ]
+        );
+        assert_eq!(text.to_string(), "Error: file a.rs");
+        assert_eq!(text.width(), 16);
+    }
+
+    #[test]
+    fn nesting() {
+        let text = parse("[on blue]a[b]b[green]c[/]d[/]e").unwrap();
+        let on_blue = pair(COLOR_WHITE, COLOR_BLUE);
+        let green_on_blue = pair(COLOR_GREEN, COLOR_BLUE);
+        assert_eq!(
+            text.spans(),
+            [
+                Span::new("a", on_blue),
+                Span::new(
+                    "b",
+                    Style::new()
+                        .with_attributes(A_BOLD)
+                        .with_color_pair(on_blue)
+                ),
+                Span::new(
+                    "c",
+                    Style::new()
+                        .with_attributes(A_BOLD)
+                        .with_color_pair(green_on_blue)
+                ),
+                Span::new(
+                    "d",
+                    Style::new()
+                        .with_attributes(A_BOLD)
+                        .with_color_pair(on_blue)
+                ),
+                Span::new("e", on_blue),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        let markup = format!("[b]{}[/]]", escape_markup("[x]"));
+        let text = parse(&markup).unwrap();
+        assert_eq!(
+            text.spans(),
+            [Span::new("[x]", A_BOLD), Span::new("]", Style::new())]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        for markup in &["[bold", "[/]", "[loud]x", "[on]x", "[]x", "a[b][/][/]"] {
+            let error = parse(markup).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidArgument, "{}", markup);
+        }
+    }
+}
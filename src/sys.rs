@@ -0,0 +1,811 @@
+//! Access to the curses implementation underneath `pancurses`.
+//!
+//! `pancurses` does not wrap every curses function (pads for example)
+//! and does not expose the `WINDOW` pointer of its `Window`s.  This
+//! module fills in those gaps by calling the same curses library that
+//! `pancurses` links against.  Windows are owned by [`Window`] instead
+//! of `pancurses::Window` so that their pointer is available.  The few
+//! functions the `ncurses` crate has no bindings for (`wgetdelay` and
+//! those taking a `cchar_t`) are declared here.
+//!
+//! [`Window`]: struct.Window.html
+
+#[cfg(unix)]
+use ncurses::ll as curses;
+#[cfg(windows)]
+use pdcurses as curses;
+
+#[cfg(unix)]
+extern "C" {
+    fn wgetdelay(window: curses::WINDOW) -> i32;
+}
+
+#[cfg(unix)]
+pub(crate) type WindowPointer = curses::WINDOW;
+#[cfg(windows)]
+pub(crate) type WindowPointer = *mut curses::WINDOW;
+
+use general::*;
+#[cfg(unix)]
+use ncurses::ll::NCURSES_ATTR_T;
+use std::ffi::CString;
+use std::ptr;
+
+#[cfg(unix)]
+fn stdscr() -> WindowPointer {
+    ncurses::stdscr()
+}
+#[cfg(windows)]
+fn stdscr() -> WindowPointer {
+    unsafe { curses::stdscr }
+}
+
+#[cfg(unix)]
+fn curscr() -> WindowPointer {
+    ncurses::curscr()
+}
+#[cfg(windows)]
+fn curscr() -> WindowPointer {
+    unsafe { curses::curscr }
+}
+
+/// A `WINDOW` pointer owned by this crate.
+///
+/// The window is deleted when this is dropped, unless it is `stdscr`.
+/// The methods mirror those of `pancurses::Window`.
+pub(crate) struct Window {
+    window: WindowPointer,
+    stdscr: bool,
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        if !self.stdscr {
+            unsafe {
+                curses::delwin(self.window);
+            }
+        }
+    }
+}
+
+/// Convert `string` for curses, or fail if it contains a nul character.
+fn c_string<T: AsRef<str>>(string: T) -> Option<CString> {
+    CString::new(string.as_ref()).ok()
+}
+
+impl Window {
+    /// Take ownership of `window`.
+    ///
+    /// Returns `None` if `window` is null.
+    fn from_ptr(window: WindowPointer) -> Option<Self> {
+        if window.is_null() {
+            None
+        } else {
+            Some(Window {
+                window,
+                stdscr: false,
+            })
+        }
+    }
+
+    /// Refer to `stdscr`.  It is never deleted.
+    pub(crate) fn stdscr() -> Self {
+        Window {
+            window: stdscr(),
+            stdscr: true,
+        }
+    }
+
+    pub(crate) fn newwin(rows: i32, columns: i32, y: i32, x: i32) -> Option<Self> {
+        Window::from_ptr(unsafe { curses::newwin(rows, columns, y, x) })
+    }
+
+    pub(crate) fn newpad(rows: i32, columns: i32) -> Option<Self> {
+        Window::from_ptr(unsafe { curses::newpad(rows, columns) })
+    }
+
+    pub(crate) fn subpad(&self, rows: i32, columns: i32, y: i32, x: i32) -> Option<Self> {
+        Window::from_ptr(unsafe { curses::subpad(self.window, rows, columns, y, x) })
+    }
+
+    pub(crate) fn subwin(&self, rows: i32, columns: i32, y: i32, x: i32) -> Option<Self> {
+        Window::from_ptr(unsafe { curses::subwin(self.window, rows, columns, y, x) })
+    }
+
+    pub(crate) fn derwin(&self, rows: i32, columns: i32, y: i32, x: i32) -> Option<Self> {
+        Window::from_ptr(unsafe { curses::derwin(self.window, rows, columns, y, x) })
+    }
+
+    pub(crate) fn dupwin(&self) -> Option<Self> {
+        Window::from_ptr(unsafe { curses::dupwin(self.window) })
+    }
+
+    /// Delete the window now rather than when it is dropped.
+    pub(crate) fn delwin(self) -> i32 {
+        let r = unsafe { curses::delwin(self.window) };
+        std::mem::forget(self);
+        r
+    }
+
+    pub(crate) fn as_ptr(&self) -> WindowPointer {
+        self.window
+    }
+
+    pub(crate) fn addch(&self, ch: Chtype) -> i32 {
+        unsafe { curses::waddch(self.window, ch) }
+    }
+
+    pub(crate) fn addstr<T: AsRef<str>>(&self, string: T) -> i32 {
+        match c_string(string) {
+            Some(s) => unsafe { curses::waddstr(self.window, s.as_ptr()) },
+            None => ERR,
+        }
+    }
+
+    pub(crate) fn attrget(&self) -> (Chtype, i16) {
+        let mut attributes = 0;
+        let mut color_pair = 0;
+        unsafe {
+            curses::wattr_get(
+                self.window,
+                &mut attributes,
+                &mut color_pair,
+                ptr::null_mut(),
+            );
+        }
+        (attributes as Chtype, color_pair)
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn attroff(&self, attributes: Chtype) -> i32 {
+        unsafe { curses::wattroff(self.window, attributes as NCURSES_ATTR_T) }
+    }
+    #[cfg(windows)]
+    pub(crate) fn attroff(&self, attributes: Chtype) -> i32 {
+        unsafe { curses::wattroff(self.window, attributes) }
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn attron(&self, attributes: Chtype) -> i32 {
+        unsafe { curses::wattron(self.window, attributes as NCURSES_ATTR_T) }
+    }
+    #[cfg(windows)]
+    pub(crate) fn attron(&self, attributes: Chtype) -> i32 {
+        unsafe { curses::wattron(self.window, attributes) }
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn attrset(&self, attributes: Chtype) -> i32 {
+        unsafe { curses::wattrset(self.window, attributes as NCURSES_ATTR_T) }
+    }
+    #[cfg(windows)]
+    pub(crate) fn attrset(&self, attributes: Chtype) -> i32 {
+        unsafe { curses::wattrset(self.window, attributes) }
+    }
+
+    pub(crate) fn bkgd(&self, ch: Chtype) -> i32 {
+        unsafe { curses::wbkgd(self.window, ch) }
+    }
+
+    pub(crate) fn bkgdset(&self, ch: Chtype) {
+        unsafe { curses::wbkgdset(self.window, ch) }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn border(
+        &self,
+        left_side: Chtype,
+        right_side: Chtype,
+        top_side: Chtype,
+        bottom_side: Chtype,
+        top_left_corner: Chtype,
+        top_right_corner: Chtype,
+        bottom_left_corner: Chtype,
+        bottom_right_corner: Chtype,
+    ) -> i32 {
+        unsafe {
+            curses::wborder(
+                self.window,
+                left_side,
+                right_side,
+                top_side,
+                bottom_side,
+                top_left_corner,
+                top_right_corner,
+                bottom_left_corner,
+                bottom_right_corner,
+            )
+        }
+    }
+
+    pub(crate) fn chgat(&self, n: i32, attributes: Chtype, color_pair: i16) -> i32 {
+        unsafe { curses::wchgat(self.window, n, attributes as _, color_pair, ptr::null_mut()) }
+    }
+
+    pub(crate) fn clear(&self) -> i32 {
+        unsafe { curses::wclear(self.window) }
+    }
+
+    pub(crate) fn clearok(&self, clear: bool) -> i32 {
+        unsafe { curses::clearok(self.window, clear as _) }
+    }
+
+    pub(crate) fn clrtobot(&self) -> i32 {
+        unsafe { curses::wclrtobot(self.window) }
+    }
+
+    pub(crate) fn clrtoeol(&self) -> i32 {
+        unsafe { curses::wclrtoeol(self.window) }
+    }
+
+    pub(crate) fn color_set(&self, color_pair: i16) -> i32 {
+        unsafe { curses::wcolor_set(self.window, color_pair, ptr::null_mut()) }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn copywin(
+        &self,
+        destination: &Window,
+        source_start_y: i32,
+        source_start_x: i32,
+        destination_start_y: i32,
+        destination_start_x: i32,
+        destination_end_y: i32,
+        destination_end_x: i32,
+        overlay: bool,
+    ) -> i32 {
+        unsafe {
+            curses::copywin(
+                self.window,
+                destination.window,
+                source_start_y,
+                source_start_x,
+                destination_start_y,
+                destination_start_x,
+                destination_end_y,
+                destination_end_x,
+                overlay as _,
+            )
+        }
+    }
+
+    pub(crate) fn delch(&self) -> i32 {
+        unsafe { curses::wdelch(self.window) }
+    }
+
+    pub(crate) fn deleteln(&self) -> i32 {
+        unsafe { curses::wdeleteln(self.window) }
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn draw_box(&self, vertical: Chtype, horizontal: Chtype) -> i32 {
+        ncurses::box_(self.window, vertical, horizontal)
+    }
+    #[cfg(windows)]
+    pub(crate) fn draw_box(&self, vertical: Chtype, horizontal: Chtype) -> i32 {
+        unsafe { curses::_box(self.window, vertical, horizontal) }
+    }
+
+    pub(crate) fn enclose(&self, y: i32, x: i32) -> bool {
+        unsafe { curses::wenclose(self.window, y, x) > 0 }
+    }
+
+    pub(crate) fn erase(&self) -> i32 {
+        unsafe { curses::werase(self.window) }
+    }
+
+    pub(crate) fn get_beg_yx(&self) -> (i32, i32) {
+        unsafe { (curses::getbegy(self.window), curses::getbegx(self.window)) }
+    }
+
+    pub(crate) fn get_cur_yx(&self) -> (i32, i32) {
+        unsafe { (curses::getcury(self.window), curses::getcurx(self.window)) }
+    }
+
+    pub(crate) fn get_max_yx(&self) -> (i32, i32) {
+        unsafe { (curses::getmaxy(self.window), curses::getmaxx(self.window)) }
+    }
+
+    pub(crate) fn getch(&self) -> Option<Input> {
+        let code = wgetch(self.window);
+        if code < 0 {
+            None
+        } else {
+            decode_input(self.window, code)
+        }
+    }
+
+    pub(crate) fn hline(&self, ch: Chtype, n: i32) -> i32 {
+        unsafe { curses::whline(self.window, ch, n) }
+    }
+
+    pub(crate) fn insch(&self, ch: Chtype) -> i32 {
+        unsafe { curses::winsch(self.window, ch) }
+    }
+
+    pub(crate) fn insdelln(&self, n: i32) -> i32 {
+        unsafe { curses::winsdelln(self.window, n) }
+    }
+
+    pub(crate) fn insertln(&self) -> i32 {
+        unsafe { curses::winsertln(self.window) }
+    }
+
+    pub(crate) fn is_linetouched(&self, line: i32) -> bool {
+        unsafe { curses::is_linetouched(self.window, line) > 0 }
+    }
+
+    pub(crate) fn is_touched(&self) -> bool {
+        unsafe { curses::is_wintouched(self.window) > 0 }
+    }
+
+    pub(crate) fn keypad(&self, keypad: bool) -> i32 {
+        unsafe { curses::keypad(self.window, keypad as _) }
+    }
+
+    pub(crate) fn mouse_trafo(&self, y: i32, x: i32, to_screen: bool) -> (i32, i32) {
+        let mut y = y;
+        let mut x = x;
+        unsafe {
+            curses::wmouse_trafo(self.window, &mut y, &mut x, to_screen as _);
+        }
+        (y, x)
+    }
+
+    pub(crate) fn mv(&self, y: i32, x: i32) -> i32 {
+        unsafe { curses::wmove(self.window, y, x) }
+    }
+
+    pub(crate) fn mvaddch(&self, y: i32, x: i32, ch: Chtype) -> i32 {
+        unsafe { curses::mvwaddch(self.window, y, x, ch) }
+    }
+
+    pub(crate) fn mvaddstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> i32 {
+        match c_string(string) {
+            Some(s) => unsafe { curses::mvwaddstr(self.window, y, x, s.as_ptr()) },
+            None => ERR,
+        }
+    }
+
+    pub(crate) fn mvchgat(
+        &self,
+        y: i32,
+        x: i32,
+        n: i32,
+        attributes: Chtype,
+        color_pair: i16,
+    ) -> i32 {
+        unsafe {
+            curses::mvwchgat(
+                self.window,
+                y,
+                x,
+                n,
+                attributes as _,
+                color_pair,
+                ptr::null_mut(),
+            )
+        }
+    }
+
+    pub(crate) fn mvderwin(&self, y: i32, x: i32) -> i32 {
+        unsafe { curses::mvderwin(self.window, y, x) }
+    }
+
+    pub(crate) fn mvinch(&self, y: i32, x: i32) -> Chtype {
+        unsafe { curses::mvwinch(self.window, y, x) }
+    }
+
+    /// Get the character, attributes and color pair at `(y, x)`.  Unlike
+    /// `mvinch`, this reads characters outside of ASCII.
+    #[cfg(all(unix, feature = "wide"))]
+    pub(crate) fn mvin_wch(&self, y: i32, x: i32) -> Option<(char, Chtype, i16)> {
+        let mut cchar = CChar([0; 8]);
+        if unsafe { mvwin_wch(self.window, y, x, &mut cchar) } == pancurses::ERR {
+            return None;
+        }
+        // `CCHARW_MAX` characters and a terminating zero.
+        let mut wch: [libc::wchar_t; 6] = [0; 6];
+        let mut attrs: curses::attr_t = 0;
+        let mut pair = 0;
+        let r = unsafe {
+            getcchar(
+                &cchar,
+                wch.as_mut_ptr(),
+                &mut attrs,
+                &mut pair,
+                ptr::null_mut(),
+            )
+        };
+        if r == pancurses::ERR {
+            return None;
+        }
+        let character =
+            std::char::from_u32(wch[0] as u32).unwrap_or(std::char::REPLACEMENT_CHARACTER);
+        Some((character, attrs, pair))
+    }
+
+    pub(crate) fn mvinsch(&self, y: i32, x: i32, ch: Chtype) -> i32 {
+        unsafe { curses::mvwinsch(self.window, y, x, ch) }
+    }
+
+    pub(crate) fn mvwin(&self, y: i32, x: i32) -> i32 {
+        unsafe { curses::mvwin(self.window, y, x) }
+    }
+
+    pub(crate) fn nodelay(&self, no_delay: bool) -> i32 {
+        unsafe { curses::nodelay(self.window, no_delay as _) as i32 }
+    }
+
+    pub(crate) fn noutrefresh(&self) -> i32 {
+        unsafe { curses::wnoutrefresh(self.window) }
+    }
+
+    pub(crate) fn overlay(&self, destination: &Window) -> i32 {
+        unsafe { curses::overlay(self.window, destination.window) }
+    }
+
+    pub(crate) fn overwrite(&self, destination: &Window) -> i32 {
+        unsafe { curses::overwrite(self.window, destination.window) }
+    }
+
+    pub(crate) fn refresh(&self) -> i32 {
+        unsafe { curses::wrefresh(self.window) }
+    }
+
+    pub(crate) fn scrollok(&self, scroll: bool) -> i32 {
+        unsafe { curses::scrollok(self.window, scroll as _) }
+    }
+
+    pub(crate) fn setscrreg(&self, top: i32, bottom: i32) -> i32 {
+        unsafe { curses::wsetscrreg(self.window, top, bottom) }
+    }
+
+    pub(crate) fn timeout(&self, milliseconds: i32) {
+        wtimeout(self.window, milliseconds)
+    }
+
+    pub(crate) fn touch(&self) -> i32 {
+        unsafe { curses::touchwin(self.window) }
+    }
+
+    pub(crate) fn touchline(&self, start: i32, count: i32) -> i32 {
+        unsafe { curses::touchline(self.window, start, count) }
+    }
+
+    pub(crate) fn touchln(&self, start: i32, count: i32, changed: bool) -> i32 {
+        unsafe { curses::wtouchln(self.window, start, count, changed as i32) }
+    }
+
+    pub(crate) fn untouch(&self) -> i32 {
+        unsafe { curses::untouchwin(self.window) }
+    }
+
+    pub(crate) fn vline(&self, ch: Chtype, n: i32) -> i32 {
+        unsafe { curses::wvline(self.window, ch, n) }
+    }
+}
+
+/// Convert a key code returned by `wgetch` to the `Input` of a special
+/// key, as `pancurses` does.
+#[cfg(unix)]
+fn special_key(code: i32) -> Option<Input> {
+    let index = if code <= KEY_F15 {
+        code - KEY_OFFSET
+    } else {
+        code - KEY_OFFSET - 48
+    };
+    if index < 0 {
+        None
+    } else {
+        SPECIAL_KEY_CODES.get(index as usize).cloned()
+    }
+}
+/// Convert a key code returned by `wgetch` to the `Input` of a special
+/// key, as `pancurses` does.
+#[cfg(windows)]
+fn special_key(code: i32) -> Option<Input> {
+    use pancurses::{
+        KEY_MOUSE, KEY_NUMPAD_ASTERISK, KEY_NUMPAD_DELETE, KEY_NUMPAD_DOWN, KEY_NUMPAD_END,
+        KEY_NUMPAD_ENTER, KEY_NUMPAD_HOME, KEY_NUMPAD_INSERT, KEY_NUMPAD_LEFT, KEY_NUMPAD_MINUS,
+        KEY_NUMPAD_PAGE_DOWN, KEY_NUMPAD_PAGE_UP, KEY_NUMPAD_PLUS, KEY_NUMPAD_RIGHT,
+        KEY_NUMPAD_SLASH, KEY_NUMPAD_UP,
+    };
+    match code {
+        KEY_RESIZE => Some(Input::KeyResize),
+        KEY_MOUSE => Some(Input::KeyMouse),
+        KEY_NUMPAD_UP => Some(Input::KeyUp),
+        KEY_NUMPAD_DOWN => Some(Input::KeyDown),
+        KEY_NUMPAD_LEFT => Some(Input::KeyLeft),
+        KEY_NUMPAD_RIGHT => Some(Input::KeyRight),
+        KEY_NUMPAD_END => Some(Input::KeyEnd),
+        KEY_NUMPAD_HOME => Some(Input::KeyHome),
+        KEY_NUMPAD_PAGE_UP => Some(Input::KeyPPage),
+        KEY_NUMPAD_PAGE_DOWN => Some(Input::KeyNPage),
+        KEY_NUMPAD_INSERT => Some(Input::KeyIC),
+        KEY_NUMPAD_DELETE => Some(Input::KeyDC),
+        KEY_NUMPAD_ENTER => Some(Input::Character('\n')),
+        KEY_NUMPAD_PLUS => Some(Input::Character('+')),
+        KEY_NUMPAD_MINUS => Some(Input::Character('-')),
+        KEY_NUMPAD_ASTERISK => Some(Input::Character('*')),
+        KEY_NUMPAD_SLASH => Some(Input::Character('/')),
+        _ => {
+            let index = if code <= KEY_F15 {
+                code - KEY_OFFSET
+            } else {
+                code - KEY_OFFSET - 48
+            };
+            if index < 0 {
+                None
+            } else {
+                SPECIAL_KEY_CODES.get(index as usize).cloned()
+            }
+        }
+    }
+}
+
+/// Decode the input starting with `code`, reading the rest of a
+/// character encoded in UTF-8 from `window`.  Returns `None` if the rest
+/// can't be read.
+#[cfg(unix)]
+fn decode_input(window: WindowPointer, code: i32) -> Option<Input> {
+    if let Some(input) = special_key(code) {
+        return Some(input);
+    }
+    let mut bytes = vec![code as u8];
+    loop {
+        match String::from_utf8(bytes.clone()) {
+            Ok(s) => return Some(Input::Character(s.chars().next().unwrap())),
+            Err(_) if bytes.len() < 4 => match wgetch(window) {
+                pancurses::ERR => return None,
+                next => bytes.push(next as u8),
+            },
+            Err(_) => return Some(Input::Unknown(code)),
+        }
+    }
+}
+/// Decode the input starting with `code`, reading the rest of a
+/// character encoded in UTF-16 from `window`.  Returns `None` if the rest
+/// can't be read.
+#[cfg(windows)]
+fn decode_input(window: WindowPointer, code: i32) -> Option<Input> {
+    if let Some(input) = special_key(code) {
+        return Some(input);
+    }
+    match std::char::from_u32(code as u32) {
+        Some(c) => Some(Input::Character(c)),
+        None => {
+            let trailing = wgetch(window);
+            if trailing == pancurses::ERR {
+                return None;
+            }
+            match std::char::decode_utf16([code as u16, trailing as u16].iter().cloned()).next() {
+                Some(Ok(c)) => Some(Input::Character(c)),
+                _ => Some(Input::Unknown(trailing)),
+            }
+        }
+    }
+}
+
+/// Encode `c` in UTF-8, as `wgetch` returns it.
+#[cfg(unix)]
+fn encode_character(c: char) -> Vec<i32> {
+    let mut buffer = [0; 4];
+    c.encode_utf8(&mut buffer).bytes().map(i32::from).collect()
+}
+/// Encode `c` in UTF-16, as `wgetch` returns it.
+#[cfg(windows)]
+fn encode_character(c: char) -> Vec<i32> {
+    let mut buffer = [0; 2];
+    c.encode_utf16(&mut buffer)
+        .iter()
+        .map(|&u| i32::from(u))
+        .collect()
+}
+
+/// Place `input` into the front of the input queue, encoded the way
+/// `wgetch` returns it.
+pub(crate) fn unget_input(input: &Input) -> i32 {
+    match *input {
+        Input::Character(c) => encode_character(c)
+            .into_iter()
+            .rev()
+            .map(ungetch)
+            .fold(OK, i32::min),
+        Input::Unknown(code) => ungetch(code),
+        #[cfg(windows)]
+        Input::KeyResize => ungetch(KEY_RESIZE),
+        #[cfg(windows)]
+        Input::KeyMouse => ungetch(pancurses::KEY_MOUSE),
+        special => match SPECIAL_KEY_CODES.iter().position(|&i| i == special) {
+            Some(index) => {
+                let code = index as i32 + KEY_OFFSET;
+                ungetch(if code <= KEY_F15 { code } else { code + 48 })
+            }
+            None => ERR,
+        },
+    }
+}
+
+/// Make the next update repaint the entire physical screen.
+pub(crate) fn clear_physical_screen() -> i32 {
+    unsafe { curses::clearok(curscr(), 1) }
+}
+
+/// Set the time to wait for the rest of an escape sequence after `ESC`
+/// is read.
+#[cfg(unix)]
+pub(crate) fn set_escdelay(milliseconds: i32) -> i32 {
+    unsafe { curses::set_escdelay(milliseconds) }
+}
+
+/// Test if the locale encodes characters in UTF-8.
+#[cfg(unix)]
+pub(crate) fn locale_is_utf8() -> bool {
+    let codeset = unsafe { libc::nl_langinfo(libc::CODESET) };
+    if codeset.is_null() {
+        return false;
+    }
+    let codeset = unsafe { std::ffi::CStr::from_ptr(codeset) }.to_string_lossy();
+    codeset.eq_ignore_ascii_case("UTF-8") || codeset.eq_ignore_ascii_case("UTF8")
+}
+/// Test if the locale encodes characters in UTF-8.  PDCurses draws
+/// characters itself instead of writing them in the encoding of the
+/// locale, so this is always the case.
+#[cfg(windows)]
+pub(crate) fn locale_is_utf8() -> bool {
+    true
+}
+
+/// Space for a `cchar_t`.  It is only filled in and read by `setcchar`
+/// and `getcchar`, so its layout doesn't need to be known, only that it
+/// fits.  ncurses' `cchar_t` is an `attr_t`, `CCHARW_MAX` (5) `wchar_t`s
+/// and an `int`, well under the 64 bytes reserved here.
+#[cfg(all(unix, feature = "wide"))]
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct CChar([u64; 8]);
+
+#[cfg(all(unix, feature = "wide"))]
+extern "C" {
+    fn setcchar(
+        wcval: *mut CChar,
+        wch: *const libc::wchar_t,
+        attrs: curses::attr_t,
+        color_pair: i16,
+        opts: *mut libc::c_void,
+    ) -> i32;
+    fn getcchar(
+        wcval: *const CChar,
+        wch: *mut libc::wchar_t,
+        attrs: *mut curses::attr_t,
+        color_pair: *mut i16,
+        opts: *mut libc::c_void,
+    ) -> i32;
+    fn mvwin_wch(window: curses::WINDOW, y: i32, x: i32, wcval: *mut CChar) -> i32;
+    fn wborder_set(
+        window: curses::WINDOW,
+        left_side: *const CChar,
+        right_side: *const CChar,
+        top_side: *const CChar,
+        bottom_side: *const CChar,
+        top_left_corner: *const CChar,
+        top_right_corner: *const CChar,
+        bottom_left_corner: *const CChar,
+        bottom_right_corner: *const CChar,
+    ) -> i32;
+}
+
+/// Draw a border of wide characters around `window`.  `chars` are in
+/// the order `wborder_set` takes them.
+#[cfg(all(unix, feature = "wide"))]
+pub(crate) fn border_set(window: WindowPointer, chars: [char; 8]) -> i32 {
+    let mut cchars = [CChar([0; 8]); 8];
+    for (cchar, &c) in cchars.iter_mut().zip(chars.iter()) {
+        let wch = [c as libc::wchar_t, 0];
+        let r = unsafe { setcchar(cchar, wch.as_ptr(), 0, 0, std::ptr::null_mut()) };
+        if r == pancurses::ERR {
+            return r;
+        }
+    }
+    unsafe {
+        wborder_set(
+            window, &cchars[0], &cchars[1], &cchars[2], &cchars[3], &cchars[4], &cchars[5],
+            &cchars[6], &cchars[7],
+        )
+    }
+}
+
+pub(crate) fn prefresh(
+    pad: WindowPointer,
+    pad_y: i32,
+    pad_x: i32,
+    screen_start_y: i32,
+    screen_start_x: i32,
+    screen_end_y: i32,
+    screen_end_x: i32,
+) -> i32 {
+    unsafe {
+        curses::prefresh(
+            pad,
+            pad_y,
+            pad_x,
+            screen_start_y,
+            screen_start_x,
+            screen_end_y,
+            screen_end_x,
+        )
+    }
+}
+
+pub(crate) fn pnoutrefresh(
+    pad: WindowPointer,
+    pad_y: i32,
+    pad_x: i32,
+    screen_start_y: i32,
+    screen_start_x: i32,
+    screen_end_y: i32,
+    screen_end_x: i32,
+) -> i32 {
+    unsafe {
+        curses::pnoutrefresh(
+            pad,
+            pad_y,
+            pad_x,
+            screen_start_y,
+            screen_start_x,
+            screen_end_y,
+            screen_end_x,
+        )
+    }
+}
+
+pub(crate) fn wresize(window: WindowPointer, rows: i32, columns: i32) -> i32 {
+    unsafe { curses::wresize(window, rows, columns) }
+}
+
+pub(crate) fn syncok(window: WindowPointer, sync: bool) -> i32 {
+    unsafe { curses::syncok(window, sync as _) }
+}
+
+pub(crate) fn wsyncup(window: WindowPointer) {
+    unsafe { curses::wsyncup(window) }
+}
+
+pub(crate) fn wsyncdown(window: WindowPointer) {
+    unsafe { curses::wsyncdown(window) }
+}
+
+pub(crate) fn wcursyncup(window: WindowPointer) {
+    unsafe { curses::wcursyncup(window) }
+}
+
+pub(crate) fn wgetch(window: WindowPointer) -> i32 {
+    unsafe { curses::wgetch(window) }
+}
+
+pub(crate) fn ungetch(code: i32) -> i32 {
+    unsafe { curses::ungetch(code) }
+}
+
+pub(crate) fn wtimeout(window: WindowPointer, delay: i32) {
+    unsafe { curses::wtimeout(window, delay) }
+}
+
+/// Get the delay set by `wtimeout` (or `nodelay`) on `window`.
+#[cfg(unix)]
+pub(crate) fn get_delay(window: WindowPointer) -> i32 {
+    unsafe { wgetdelay(window) }
+}
+/// Get the delay set by `wtimeout` (or `nodelay`) on `window`.
+#[cfg(windows)]
+pub(crate) fn get_delay(window: WindowPointer) -> i32 {
+    let window = unsafe { &*window };
+    if window._nodelay != 0 {
+        0
+    } else if window._delayms == 0 {
+        -1
+    } else {
+        window._delayms
+    }
+}
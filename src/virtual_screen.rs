@@ -0,0 +1,494 @@
+use backend::Backend;
+use cell::Cell;
+use color::ColorPairId;
+use error::*;
+use general::*;
+use key::*;
+use point::*;
+use std::collections::VecDeque;
+
+const TAB_SIZE: i32 = 8;
+
+/// An in-memory [`Backend`] that records what is drawn on it instead of
+/// drawing on a terminal.
+///
+/// Characters are placed the way curses places them: the cursor
+/// advances after each character, wraps at the end of each line, and
+/// scrolls the screen up at the bottom if scrolling is enabled (see
+/// [`set_scroll_enabled`]).  Input is read from a queue of scripted
+/// [`Input`]s (see [`push_input`]).
+///
+/// A `VirtualScreen` doesn't require curses to be initialized.
+///
+/// [`Backend`]: trait.Backend.html
+/// [`set_scroll_enabled`]: trait.Backend.html#tymethod.set_scroll_enabled
+/// [`Input`]: enum.Input.html
+/// [`push_input`]: struct.VirtualScreen.html#method.push_input
+#[derive(Clone, Debug)]
+pub struct VirtualScreen {
+    size: Dimension,
+    cells: Vec<Cell>,
+    point: Point,
+    /// The attributes, including the color pair, applied to characters
+    /// as they are drawn.
+    attributes: Chtype,
+    scroll: bool,
+    input: VecDeque<Input>,
+    refreshes: usize,
+}
+
+impl VirtualScreen {
+    /// Create a blank `VirtualScreen` of `size`.
+    ///
+    /// Negative dimensions are treated as zero.
+    pub fn new<D: Into<Dimension>>(size: D) -> Self {
+        let size = size.into();
+        let size = Dimension {
+            rows: size.rows.max(0),
+            columns: size.columns.max(0),
+        };
+        VirtualScreen {
+            size,
+            cells: vec![blank(); (size.rows * size.columns) as usize],
+            point: Point { y: 0, x: 0 },
+            attributes: 0,
+            scroll: false,
+            input: VecDeque::new(),
+            refreshes: 0,
+        }
+    }
+
+    /// Add `input` to the end of the queue read by [`read_char`] and
+    /// [`read_key`].
+    ///
+    /// [`read_char`]: trait.Backend.html#tymethod.read_char
+    /// [`read_key`]: trait.Backend.html#tymethod.read_key
+    pub fn push_input(&mut self, input: Input) {
+        self.input.push_back(input);
+    }
+    /// Add each character of `string` to the end of the input queue.
+    ///
+    /// See [`push_input`].
+    ///
+    /// [`push_input`]: struct.VirtualScreen.html#method.push_input
+    pub fn push_str(&mut self, string: &str) {
+        self.input.extend(string.chars().map(Input::Character));
+    }
+    /// Get the number of inputs that haven't been read yet.
+    pub fn pending_inputs(&self) -> usize {
+        self.input.len()
+    }
+
+    /// Test if scrolling is enabled.
+    pub fn scroll_enabled(&self) -> bool {
+        self.scroll
+    }
+    /// Get the number of times [`refresh`] has been called.
+    ///
+    /// [`refresh`]: trait.Backend.html#tymethod.refresh
+    pub fn refresh_count(&self) -> usize {
+        self.refreshes
+    }
+
+    fn index(&self, p: Point) -> usize {
+        (p.y * self.size.columns + p.x) as usize
+    }
+    fn contains(&self, p: Point) -> bool {
+        p.y >= 0 && p.x >= 0 && p.y < self.size.rows && p.x < self.size.columns
+    }
+
+    /// Apply the current attributes to `cell`.  As in curses, the color
+    /// pair of `cell` takes precedence over the current one.
+    fn render(&self, mut cell: Cell) -> Cell {
+        cell.attributes |= self.attributes & A_ATTRIBUTES & !A_COLOR;
+        if cell.color_pair == ColorPairId::default() {
+            cell.color_pair = Cell::from(self.attributes & A_COLOR).color_pair;
+        }
+        cell
+    }
+
+    fn scroll_up(&mut self) {
+        let columns = self.size.columns as usize;
+        self.cells.drain(..columns);
+        self.cells.extend((0..columns).map(|_| blank()));
+    }
+
+    /// Move the cursor to the start of the next line, scrolling if it
+    /// is on the last line.
+    fn new_line(&mut self, function: &'static str) -> Result<(), Error> {
+        if self.point.y + 1 < self.size.rows {
+            self.point.y += 1;
+        } else if self.scroll {
+            self.scroll_up();
+        } else {
+            return Err(Error::new(ErrorKind::Failed, function));
+        }
+        self.point.x = 0;
+        Ok(())
+    }
+
+    /// Store `cell` at the cursor and advance the cursor.
+    fn put_cell(&mut self, function: &'static str, cell: Cell) -> Result<(), Error> {
+        if !self.contains(self.point) {
+            return Err(Error::new(ErrorKind::Failed, function));
+        }
+        let index = self.index(self.point);
+        self.cells[index] = self.render(cell);
+        if self.point.x + 1 < self.size.columns {
+            self.point.x += 1;
+            Ok(())
+        } else {
+            self.new_line(function)
+        }
+    }
+
+    /// Put `cell` at the cursor, interpreting control characters the
+    /// way `addch` does.
+    fn put(&mut self, function: &'static str, cell: Cell) -> Result<(), Error> {
+        let plain = |character| Cell { character, ..cell };
+        match cell.character {
+            '\n' => {
+                self.clear_to_end_of_line()?;
+                self.new_line(function)
+            }
+            '\r' => {
+                self.point.x = 0;
+                Ok(())
+            }
+            '\x08' => {
+                self.point.x = (self.point.x - 1).max(0);
+                Ok(())
+            }
+            '\t' => {
+                let stop = (self.point.x / TAB_SIZE + 1) * TAB_SIZE;
+                let y = self.point.y;
+                while self.point.y == y && self.point.x < stop {
+                    self.put_cell(function, plain(' '))?;
+                }
+                Ok(())
+            }
+            c @ '\0'..='\x1f' => {
+                self.put_cell(function, plain('^'))?;
+                self.put_cell(function, plain((c as u8 + b'@') as char))
+            }
+            '\x7f' => {
+                self.put_cell(function, plain('^'))?;
+                self.put_cell(function, plain('?'))
+            }
+            _ => self.put_cell(function, cell),
+        }
+    }
+
+    /// Put `ch` at `p` without moving the cursor.
+    fn put_at(&mut self, p: Point, ch: Chtype) {
+        if self.contains(p) {
+            let index = self.index(p);
+            self.cells[index] = self.render(Cell::from(ch));
+        }
+    }
+
+    /// Blank from `p` to the end of the screen.
+    fn blank_from(&mut self, p: Point) {
+        let start = self.index(p);
+        for cell in &mut self.cells[start..] {
+            *cell = blank();
+        }
+    }
+}
+
+fn blank() -> Cell {
+    Cell::from(' ' as Chtype)
+}
+
+/// The character `ACS_*` would be if curses stored it in the alternate
+/// character set, so that borders look the same as they do in a
+/// [`Window`].
+///
+/// [`Window`]: struct.Window.html
+fn alternate(ch: Chtype, default: char) -> Chtype {
+    if ch == 0 {
+        default as Chtype | A_ALTCHARSET
+    } else {
+        ch
+    }
+}
+
+impl Backend for VirtualScreen {
+    fn size(&self) -> Dimension {
+        self.size
+    }
+    fn point(&self) -> Point {
+        self.point
+    }
+    fn move_to(&mut self, p: Point) -> Result<(), Error> {
+        if self.contains(p) {
+            self.point = p;
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::OutOfBounds, "move").with_arguments(format!("{:?}", p)))
+        }
+    }
+
+    fn put_char(&mut self, ch: Chtype) -> Result<(), Error> {
+        self.put("addch", Cell::from(ch))
+    }
+    fn put_str(&mut self, string: &str) -> Result<(), Error> {
+        for character in string.chars() {
+            let cell = Cell {
+                character,
+                ..blank()
+            };
+            self.put("addstr", cell)?;
+        }
+        Ok(())
+    }
+    fn move_put_char(&mut self, p: Point, ch: Chtype) -> Result<(), Error> {
+        self.move_to(p)
+            .map_err(|e| Error::new(e.kind(), "mvaddch").with_arguments(format!("{:?}", p)))?;
+        self.put("mvaddch", Cell::from(ch))
+    }
+    fn move_put_str(&mut self, p: Point, string: &str) -> Result<(), Error> {
+        self.move_to(p)
+            .map_err(|e| Error::new(e.kind(), "mvaddstr").with_arguments(format!("{:?}", p)))?;
+        self.put_str(string)
+    }
+
+    fn attributes(&self) -> (Chtype, ColorPairId) {
+        (self.attributes, Cell::from(self.attributes).color_pair)
+    }
+    fn set_attributes(&mut self, attributes: Chtype) -> Result<(), Error> {
+        self.attributes = attributes & A_ATTRIBUTES;
+        Ok(())
+    }
+    fn turn_on_attributes(&mut self, attributes: Chtype) -> Result<(), Error> {
+        let attributes = attributes & A_ATTRIBUTES;
+        if attributes & A_COLOR != 0 {
+            self.attributes &= !A_COLOR;
+        }
+        self.attributes |= attributes;
+        Ok(())
+    }
+    fn turn_off_attributes(&mut self, attributes: Chtype) -> Result<(), Error> {
+        let attributes = attributes & A_ATTRIBUTES;
+        if attributes & A_COLOR != 0 {
+            self.attributes &= !A_COLOR;
+        }
+        self.attributes &= !(attributes & !A_COLOR);
+        Ok(())
+    }
+    fn set_color(&mut self, color_pair: ColorPairId) -> Result<(), Error> {
+        self.attributes = (self.attributes & !A_COLOR) | Chtype::from(color_pair);
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Error> {
+        self.erase()
+    }
+    fn erase(&mut self) -> Result<(), Error> {
+        self.blank_from(Point { y: 0, x: 0 });
+        self.point = Point { y: 0, x: 0 };
+        Ok(())
+    }
+    fn clear_to_bottom(&mut self) -> Result<(), Error> {
+        if self.contains(self.point) {
+            self.blank_from(self.point);
+        }
+        Ok(())
+    }
+    fn clear_to_end_of_line(&mut self) -> Result<(), Error> {
+        if self.contains(self.point) {
+            let start = self.index(self.point);
+            let end = self.index(Point {
+                y: self.point.y + 1,
+                x: 0,
+            });
+            for cell in &mut self.cells[start..end] {
+                *cell = blank();
+            }
+        }
+        Ok(())
+    }
+    fn set_scroll_enabled(&mut self, scroll: bool) -> Result<(), Error> {
+        self.scroll = scroll;
+        Ok(())
+    }
+
+    fn draw_border(
+        &mut self,
+        left_side: Chtype,
+        right_side: Chtype,
+        top_side: Chtype,
+        bottom_side: Chtype,
+        top_left_corner: Chtype,
+        top_right_corner: Chtype,
+        bottom_left_corner: Chtype,
+        bottom_right_corner: Chtype,
+    ) -> Result<(), Error> {
+        let bottom = self.size.rows - 1;
+        let right = self.size.columns - 1;
+        for x in 1..right {
+            self.put_at(Point { y: 0, x }, alternate(top_side, 'q'));
+            self.put_at(Point { y: bottom, x }, alternate(bottom_side, 'q'));
+        }
+        for y in 1..bottom {
+            self.put_at(Point { y, x: 0 }, alternate(left_side, 'x'));
+            self.put_at(Point { y, x: right }, alternate(right_side, 'x'));
+        }
+        self.put_at(Point { y: 0, x: 0 }, alternate(top_left_corner, 'l'));
+        self.put_at(Point { y: 0, x: right }, alternate(top_right_corner, 'k'));
+        self.put_at(
+            Point { y: bottom, x: 0 },
+            alternate(bottom_left_corner, 'm'),
+        );
+        let bottom_right = Point {
+            y: bottom,
+            x: right,
+        };
+        self.put_at(bottom_right, alternate(bottom_right_corner, 'j'));
+        Ok(())
+    }
+
+    fn cell_at(&self, p: Point) -> Result<Cell, Error> {
+        if self.contains(p) {
+            Ok(self.cells[self.index(p)])
+        } else {
+            let size = Dimension {
+                rows: 1,
+                columns: 1,
+            };
+            Err(Error::new(ErrorKind::OutOfBounds, "mvinch")
+                .with_arguments(format!("{:?}, {:?}", p, size)))
+        }
+    }
+
+    fn read_char(&mut self) -> Option<Input> {
+        self.input.pop_front()
+    }
+    fn read_key(&mut self) -> Option<KeyEvent> {
+        let event = KeyEvent::from(self.read_char()?);
+        if event == KeyEvent::from(Key::Escape) {
+            if let Some(next) = self.read_char() {
+                return Some(KeyEvent::from(next).with_modifiers(Modifiers::ALT));
+            }
+        }
+        Some(event)
+    }
+
+    fn refresh(&mut self) -> Result<(), Error> {
+        self.refreshes += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_at_end_of_line() {
+        let mut screen = VirtualScreen::new((2, 4));
+        screen.put_str("abcdef").unwrap();
+        assert_eq!(screen.text(), ["abcd", "ef  "]);
+        assert_eq!(screen.point(), Point { y: 1, x: 2 });
+    }
+
+    #[test]
+    fn fails_at_bottom_right_without_scrolling() {
+        let mut screen = VirtualScreen::new((2, 3));
+        assert!(screen.put_str("abcdef").is_err());
+        assert_eq!(screen.text(), ["abc", "def"]);
+        assert_eq!(screen.point(), Point { y: 1, x: 2 });
+    }
+
+    #[test]
+    fn scrolls_at_bottom() {
+        let mut screen = VirtualScreen::new((2, 3));
+        screen.set_scroll_enabled(true).unwrap();
+        screen.put_str("ab\ncd\nef").unwrap();
+        assert_eq!(screen.text(), ["cd ", "ef "]);
+        screen.put_char('g' as Chtype).unwrap();
+        assert_eq!(screen.text(), ["efg", "   "]);
+        assert_eq!(screen.point(), Point { y: 1, x: 0 });
+        screen.put_str("hi").unwrap();
+        assert_eq!(screen.text(), ["efg", "hi "]);
+        assert_eq!(screen.point(), Point { y: 1, x: 2 });
+    }
+
+    #[test]
+    fn control_characters() {
+        let mut screen = VirtualScreen::new((3, 10));
+        screen.put_str("xxxxxxxxxx").unwrap();
+        screen
+            .move_put_str(Point { y: 0, x: 2 }, "a\nb\tc\x01")
+            .unwrap();
+        assert_eq!(screen.text(), ["xxa       ", "b       c^", "A         "]);
+        assert_eq!(screen.point(), Point { y: 2, x: 1 });
+        screen.put_str("\rd\x08e").unwrap();
+        assert_eq!(screen.line_text(2).unwrap(), "e         ");
+    }
+
+    #[test]
+    fn clear_to_end_of_line() {
+        let mut screen = VirtualScreen::new((2, 4));
+        screen.put_str("abcdefg").unwrap();
+        screen.move_to(Point { y: 0, x: 1 }).unwrap();
+        screen.clear_to_end_of_line().unwrap();
+        assert_eq!(screen.text(), ["a   ", "efg "]);
+        screen.clear_to_bottom().unwrap();
+        assert_eq!(screen.text(), ["a   ", "    "]);
+        assert_eq!(screen.point(), Point { y: 0, x: 1 });
+    }
+
+    #[test]
+    fn attributes_apply_to_drawn_characters() {
+        let mut screen = VirtualScreen::new((1, 3));
+        let pair = ColorPairId::new(2);
+        screen.turn_on_attributes(A_BOLD).unwrap();
+        screen.set_color(pair).unwrap();
+        screen.put_char('a' as Chtype | A_UNDERLINE).unwrap();
+        assert_eq!(
+            screen.cell_at(Point { y: 0, x: 0 }),
+            Ok(Cell {
+                character: 'a',
+                attributes: A_BOLD | A_UNDERLINE,
+                color_pair: pair,
+            })
+        );
+        assert_eq!(screen.attributes().1, pair);
+        screen.turn_off_attributes(A_BOLD).unwrap();
+        screen.put_char('b' as Chtype).unwrap();
+        assert_eq!(screen.cell_at(Point { y: 0, x: 1 }).unwrap().attributes, 0);
+        assert_eq!(
+            screen.cell_at(Point { y: 0, x: 3 }).unwrap_err().kind(),
+            ErrorKind::OutOfBounds
+        );
+    }
+
+    #[test]
+    fn default_border_uses_alternate_characters() {
+        let mut screen = VirtualScreen::new((3, 3));
+        screen.draw_box(0, '-' as Chtype).unwrap();
+        assert_eq!(screen.text(), ["l-k", "x x", "m-j"]);
+        let corner = screen.cell_at(Point { y: 0, x: 0 }).unwrap();
+        assert_eq!(corner.attributes, A_ALTCHARSET);
+        assert_eq!(screen.point(), Point { y: 0, x: 0 });
+    }
+
+    #[test]
+    fn reads_scripted_input() {
+        let mut screen = VirtualScreen::new((1, 1));
+        screen.push_input(Input::KeyLeft);
+        screen.push_str("\x1bx\x18");
+        assert_eq!(screen.pending_inputs(), 4);
+        assert_eq!(screen.read_key(), Some(Key::Left.into()));
+        assert_eq!(
+            screen.read_key(),
+            Some(KeyEvent::new(Key::Char('x'), Modifiers::ALT))
+        );
+        assert_eq!(
+            screen.read_key(),
+            Some(KeyEvent::new(Key::Char('x'), Modifiers::CTRL))
+        );
+        assert_eq!(screen.read_char(), None);
+    }
+}
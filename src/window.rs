@@ -1,6 +1,17 @@
+use border::{self, Border};
+use cell::*;
+use color::{Color, ColorPairId};
+use error::*;
 use general::*;
+use key::*;
 use point::*;
+use rect::Rect;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
 use std::time::Duration;
+use style::*;
+use styled_text::StyledText;
+use sys;
+use wrap::{self, WrapOptions};
 
 pub enum EndOfLineOrNumber {
     EndOfLine,
@@ -22,32 +33,123 @@ impl From<i32> for EndOfLineOrNumber {
     }
 }
 
+/// The memory of a curses window.
+///
+/// A `Handle` keeps the `Handle` of the window it depends upon alive.
+/// Fields are dropped in order, so the window is deleted before its
+/// parent is released.
+struct Handle {
+    w: sys::Window,
+    /// Held while the cursor is moved by a method that takes `&self`,
+    /// so that other readers don't see it moved.
+    cursor: Mutex<()>,
+    parent: Option<Arc<Handle>>,
+}
+
+impl Handle {
+    fn new(w: sys::Window, parent: Option<Arc<Handle>>) -> Self {
+        Handle {
+            w,
+            cursor: Mutex::new(()),
+            parent,
+        }
+    }
+
+    fn lock_cursor(&self) -> MutexGuard<'_, ()> {
+        self.cursor.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+unsafe impl Send for Handle {}
+unsafe impl Sync for Handle {}
+
 /// A curses window.
 ///
 /// It will clean up itself on destruction.
 ///
+/// Sub windows (see [`create_sub_window`] and [`create_derived_window`])
+/// share memory with their parent.  To keep this memory safe, a sub
+/// window keeps its parent's memory alive; dropping the parent `Window`
+/// while sub windows are still alive defers deleting it until the last sub
+/// window is dropped.  Explicitly deleting a `Window` with
+/// [`delete_window`] while it has sub windows fails with
+/// [`ErrorKind::WindowInUse`].
+///
+/// Similarly, `Window`s created by [`Curses`] keep `stdscr` alive.
+/// Curses never frees `Window`s in `endwin`, so a `Window` may safely be
+/// used after [`Curses::end_curses`] (though doing so will re-enter curses
+/// mode).
+///
 /// Many curses functions have been renamed for one reason or another.  All
 /// renamed functions state the curses function they corollate to.
+///
+/// [`create_sub_window`]: struct.Window.html#method.create_sub_window
+/// [`create_derived_window`]: struct.Window.html#method.create_derived_window
+/// [`delete_window`]: struct.Window.html#method.delete_window
+/// [`ErrorKind::WindowInUse`]: enum.ErrorKind.html#variant.WindowInUse
+/// [`Curses`]: struct.Curses.html
+/// [`Curses::end_curses`]: struct.Curses.html#method.end_curses
 pub struct Window {
-    w: pancurses::Window,
+    handle: Arc<Handle>,
 }
 
 impl Window {
-    pub(crate) fn new(w: pancurses::Window) -> Self {
-        Window { w }
+    pub(crate) fn new(w: sys::Window) -> Self {
+        Window {
+            handle: Arc::new(Handle::new(w, None)),
+        }
+    }
+
+    /// Create a `Window` that depends on the memory of `parent`.
+    pub(crate) fn with_parent(w: sys::Window, parent: &Window) -> Self {
+        Window {
+            handle: Arc::new(Handle::new(w, Some(parent.handle.clone()))),
+        }
+    }
+
+    pub(crate) fn raw(&self) -> &sys::Window {
+        &self.handle.w
+    }
+
+    pub(crate) fn as_ptr(&self) -> sys::WindowPointer {
+        self.handle.w.as_ptr()
+    }
+
+    /// Refer to this `Window` without keeping it alive.
+    pub(crate) fn downgrade(&self) -> WeakWindow {
+        WeakWindow {
+            handle: Arc::downgrade(&self.handle),
+        }
+    }
+
+    /// Check the result of a curses function that was given the
+    /// `Window`-relative point `p`.
+    ///
+    /// If `p` is outside of the `Window` the error is categorized as
+    /// [`ErrorKind::OutOfBounds`].
+    fn check_point(&self, function: &'static str, r: i32, p: Point) -> Result<(), Error> {
+        check(function, r).map_err(|e| {
+            let size = self.size();
+            let e = e.with_arguments(format!("{:?}", p));
+            if p.y < 0 || p.x < 0 || p.y >= size.rows || p.x >= size.columns {
+                e.with_kind(ErrorKind::OutOfBounds)
+            } else {
+                e
+            }
+        })
     }
 
     /// Put a character at the point.
     ///
     /// This corresponds to `addch`.
-    pub fn put_char<T: Into<Chtype>>(&mut self, ch: T) -> Result<(), ()> {
-        check(self.w.addch(ch.into()))
+    pub fn put_char<T: Into<Chtype>>(&mut self, ch: T) -> Result<(), Error> {
+        check("addch", self.handle.w.addch(ch.into()))
     }
     /// Put a string at the point.
     ///
     /// This corresponds to `addch`.
-    pub fn put_str<T: AsRef<str>>(&mut self, string: T) -> Result<(), ()> {
-        check(self.w.addstr(string))
+    pub fn put_str<T: AsRef<str>>(&mut self, string: T) -> Result<(), Error> {
+        check("addstr", self.handle.w.addstr(string))
     }
     /// Print a formatted string at the point.
     ///
@@ -55,7 +157,7 @@ impl Window {
     /// under the hood because that function cannot be safe to use
     /// within rust code because rust does not allow for variadic
     /// arguments.
-    pub fn printw(&mut self, args: std::fmt::Arguments) -> Result<(), ()> {
+    pub fn printw(&mut self, args: std::fmt::Arguments) -> Result<(), Error> {
         self.put_str(args.to_string())
     }
     /// Put the contents of `source` that overlap with this `Window`.
@@ -64,7 +166,7 @@ impl Window {
     /// overlapping portions are copied.
     ///
     /// This corresponds to `overwrite` but *with the arguments flipped*.
-    pub fn put_window(&mut self, source: &Window) -> Result<(), ()> {
+    pub fn put_window(&mut self, source: &Window) -> Result<(), Error> {
         source.overwrite_onto(self)
     }
     /// Put the contents of `source` in a region at a region of this `Window`.
@@ -80,9 +182,38 @@ impl Window {
         destination_end: P2,
         source: &Window,
         source_start: P3,
-    ) -> Result<(), ()> {
+    ) -> Result<(), Error> {
         source.overwrite_region_onto(source_start, self, destination_start, destination_end)
     }
+    /// Put the contents of `source` starting at `source_start` into the
+    /// `destination` rectangle of this `Window`.
+    ///
+    /// This is [`put_window_region`] with the destination given as a
+    /// [`Rect`].  Nothing is copied if `destination` is empty.
+    ///
+    /// This corresponds to `copywin` but *with the arguments flipped* and a
+    /// final argument of `true`.
+    ///
+    /// [`put_window_region`]: struct.Window.html#method.put_window_region
+    /// [`Rect`]: struct.Rect.html
+    pub fn put_window_rect<R: Into<Rect>, P: Into<Point>>(
+        &mut self,
+        destination: R,
+        source: &Window,
+        source_start: P,
+    ) -> Result<(), Error> {
+        let destination = destination.into();
+        if destination.is_empty() {
+            return Ok(());
+        }
+        // The end given to `copywin` is inclusive.
+        let end = destination.end()
+            - Dimension {
+                rows: 1,
+                columns: 1,
+            };
+        self.put_window_region(destination.origin, end, source, source_start)
+    }
     /// Put the non-blank contents of `source` that overlap with this
     /// `Window`.
     ///
@@ -90,7 +221,7 @@ impl Window {
     /// overlapping portions are copied.
     ///
     /// This corresponds to `overlay` but *with the arguments flipped*.
-    pub fn put_window_text(&mut self, source: &Window) -> Result<(), ()> {
+    pub fn put_window_text(&mut self, source: &Window) -> Result<(), Error> {
         source.overlay_onto(self)
     }
     /// Put the non-blank contents of `source` in a region at a region of this `Window`.
@@ -106,21 +237,21 @@ impl Window {
         destination_end: P2,
         source: &Window,
         source_start: P3,
-    ) -> Result<(), ()> {
+    ) -> Result<(), Error> {
         source.overlay_region_onto(source_start, self, destination_start, destination_end)
     }
 
     /// Put the contents of this `Window` onto `destination` where they overlap.
     ///
     /// This corresponds to `overwrite`.
-    pub fn overwrite_onto(&self, destination: &mut Self) -> Result<(), ()> {
-        check(self.w.overwrite(&destination.w))
+    pub fn overwrite_onto(&self, destination: &mut Self) -> Result<(), Error> {
+        check("overwrite", self.handle.w.overwrite(&destination.handle.w))
     }
     /// Put the non-blank contents of this `Window` onto `destination` where they overlap.
     ///
     /// This corresponds to `overlay`.
-    pub fn overlay_onto(&self, destination: &mut Self) -> Result<(), ()> {
-        check(self.w.overlay(&destination.w))
+    pub fn overlay_onto(&self, destination: &mut Self) -> Result<(), Error> {
+        check("overlay", self.handle.w.overlay(&destination.handle.w))
     }
 
     /// Overwrite this `Window` on top of the `destination`.
@@ -136,12 +267,12 @@ impl Window {
         destination: &mut Window,
         destination_start: P2,
         destination_end: P3,
-    ) -> Result<(), ()> {
+    ) -> Result<(), Error> {
         let source_start = source_start.into();
         let destination_start = destination_start.into();
         let destination_end = destination_end.into();
-        check(self.w.copywin(
-            &destination.w,
+        let r = self.handle.w.copywin(
+            &destination.handle.w,
             source_start.y,
             source_start.x,
             destination_start.y,
@@ -149,7 +280,13 @@ impl Window {
             destination_end.y,
             destination_end.x,
             true,
-        ))
+        );
+        check("copywin", r).map_err(|e| {
+            e.with_arguments(format!(
+                "{:?}, {:?}, {:?}",
+                source_start, destination_start, destination_end
+            ))
+        })
     }
     /// Overlay this `Window`'s text on top of the `destination`.
     ///
@@ -164,12 +301,12 @@ impl Window {
         destination: &mut Window,
         destination_start: P2,
         destination_end: P3,
-    ) -> Result<(), ()> {
+    ) -> Result<(), Error> {
         let source_start = source_start.into();
         let destination_start = destination_start.into();
         let destination_end = destination_end.into();
-        check(self.w.copywin(
-            &destination.w,
+        let r = self.handle.w.copywin(
+            &destination.handle.w,
             source_start.y,
             source_start.x,
             destination_start.y,
@@ -177,32 +314,39 @@ impl Window {
             destination_end.y,
             destination_end.x,
             true,
-        ))
+        );
+        check("copywin", r).map_err(|e| {
+            e.with_arguments(format!(
+                "{:?}, {:?}, {:?}",
+                source_start, destination_start, destination_end
+            ))
+        })
     }
 
     /// Get the attributes of the character at the point.
     ///
     /// This corresponds to `attrget`.
-    pub fn attributes(&self) -> (Chtype, i16) {
-        self.w.attrget()
+    pub fn attributes(&self) -> (Chtype, ColorPairId) {
+        let (attributes, color_pair) = self.handle.w.attrget();
+        (attributes, ColorPairId::new(color_pair))
     }
     /// Turn off the following attributes of the character at the point.
     ///
     /// This corresponds to `attroff`.
-    pub fn turn_off_attributes<T: Into<Chtype>>(&mut self, attributes: T) -> Result<(), ()> {
-        check(self.w.attroff(attributes))
+    pub fn turn_off_attributes<T: Into<Chtype>>(&mut self, attributes: T) -> Result<(), Error> {
+        check("attroff", self.handle.w.attroff(attributes.into()))
     }
     /// Turn on the following attributes of the character at the point.
     ///
     /// This corresponds to `attron`.
-    pub fn turn_on_attributes<T: Into<Chtype>>(&mut self, attributes: T) -> Result<(), ()> {
-        check(self.w.attron(attributes))
+    pub fn turn_on_attributes<T: Into<Chtype>>(&mut self, attributes: T) -> Result<(), Error> {
+        check("attron", self.handle.w.attron(attributes.into()))
     }
     /// Set the attributes of the character at the point.
     ///
     /// This corresponds to `attrset`.
-    pub fn set_attributes<T: Into<Chtype>>(&mut self, attributes: T) -> Result<(), ()> {
-        check(self.w.attrset(attributes))
+    pub fn set_attributes<T: Into<Chtype>>(&mut self, attributes: T) -> Result<(), Error> {
+        check("attrset", self.handle.w.attrset(attributes.into()))
     }
     /// Turn off the following attributes of the character at the point.
     ///
@@ -211,11 +355,15 @@ impl Window {
         &mut self,
         n: N,
         attributes: T,
-        color_pair: i16,
-    ) -> Result<(), ()> {
+        color_pair: ColorPairId,
+    ) -> Result<(), Error> {
         check(
-            self.w
-                .chgat(n.into().unwrap_number_or(-1), attributes.into(), color_pair),
+            "chgat",
+            self.handle.w.chgat(
+                n.into().unwrap_number_or(-1),
+                attributes.into(),
+                color_pair.number(),
+            ),
         )
     }
 
@@ -223,7 +371,7 @@ impl Window {
     ///
     /// This corresponds to `bkgdset`.
     pub fn set_background<T: Into<Chtype>>(&mut self, ch: T) {
-        self.w.bkgdset(ch)
+        self.handle.w.bkgdset(ch.into())
     }
     /// Set the background of the `Window` and apply it.
     ///
@@ -232,8 +380,11 @@ impl Window {
     /// `background`.
     ///
     /// This corresponds to `bkgd`.
-    pub fn set_background_and_apply<T: Into<Chtype>>(&mut self, background: T) -> Result<(), ()> {
-        check(self.w.bkgd(background))
+    pub fn set_background_and_apply<T: Into<Chtype>>(
+        &mut self,
+        background: T,
+    ) -> Result<(), Error> {
+        check("bkgd", self.handle.w.bkgd(background.into()))
     }
 
     /// Clear the screen.
@@ -248,16 +399,16 @@ impl Window {
     /// [`erase`]: struct.Window.html#method.erase
     /// [`refresh`]: struct.Window.html#method.refresh
     /// [`refresh_force_clear`]: struct.Window.html#method.refresh_force_clear
-    pub fn clear(&mut self) -> Result<(), ()> {
-        check(self.w.clear())
+    pub fn clear(&mut self) -> Result<(), Error> {
+        check("clear", self.handle.w.clear())
     }
     /// Clear the virtual screen.
     ///
     /// See [`clear`] for a comparison of these two methods.
     ///
     /// [`clear`]: struct.Window.html#method.clear
-    pub fn erase(&mut self) -> Result<(), ()> {
-        check(self.w.erase())
+    pub fn erase(&mut self) -> Result<(), Error> {
+        check("erase", self.handle.w.erase())
     }
     /// Erase all characters after the point.
     ///
@@ -265,22 +416,74 @@ impl Window {
     /// after the one the point is on.
     ///
     /// This corresponds to `clrtobot`.
-    pub fn clear_to_bottom(&mut self) -> Result<(), ()> {
-        check(self.w.clrtobot())
+    pub fn clear_to_bottom(&mut self) -> Result<(), Error> {
+        check("clrtobot", self.handle.w.clrtobot())
     }
     /// Erase all characters to the right of the point on this line.
     ///
     /// This corresponds to `clrtobot`.
-    pub fn clear_to_end_of_line(&mut self) -> Result<(), ()> {
-        check(self.w.clrtoeol())
+    pub fn clear_to_end_of_line(&mut self) -> Result<(), Error> {
+        check("clrtoeol", self.handle.w.clrtoeol())
     }
 
     /// Set the current color of the given window to the
     /// foregrond/background pair `color_pair`.
     ///
     /// This corresponds to `color_set`.
-    pub fn set_color(&mut self, color_pair: i16) -> Result<(), ()> {
-        check(self.w.color_set(color_pair))
+    pub fn set_color(&mut self, color_pair: ColorPairId) -> Result<(), Error> {
+        check("color_set", self.handle.w.color_set(color_pair.number()))
+    }
+    /// Apply `style` until the returned [`StyleGuard`] is dropped.
+    ///
+    /// The attributes and color pair are restored when the guard is
+    /// dropped, even on an early return.
+    ///
+    /// [`StyleGuard`]: struct.StyleGuard.html
+    pub fn style_guard<S: Into<Style>>(&mut self, style: S) -> Result<StyleGuard<'_>, Error> {
+        StyleGuard::new(self, style.into())
+    }
+    /// Apply `style` while running `f`, then restore the attributes and
+    /// color pair.
+    ///
+    /// See [`style_guard`].
+    ///
+    /// [`style_guard`]: struct.Window.html#method.style_guard
+    pub fn with_style<S: Into<Style>, F, R>(&mut self, style: S, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut Window) -> Result<R, Error>,
+    {
+        let mut guard = self.style_guard(style)?;
+        let result = f(&mut guard)?;
+        guard.restore()?;
+        Ok(result)
+    }
+    /// Put `text` at the point, drawing each span with its style.
+    ///
+    /// The attributes and color pair are restored afterwards, even if
+    /// putting the text fails.
+    ///
+    /// This corresponds to `addstr`.
+    pub fn put_styled(&mut self, text: &StyledText) -> Result<(), Error> {
+        for span in text.spans() {
+            self.with_style(span.style, |window| window.put_str(&span.text))?;
+        }
+        Ok(())
+    }
+    /// Put `text` containing ANSI escape sequences at the point, drawing
+    /// it in the colors and attributes the sequences select.
+    ///
+    /// See [`StyledText::from_ansi`] for the supported sequences.  To
+    /// draw on the `Window` of [`Curses`], parse the text with
+    /// `from_ansi` first and then use [`put_styled`].
+    ///
+    /// This corresponds to `addstr`.
+    ///
+    /// [`StyledText::from_ansi`]: struct.StyledText.html#method.from_ansi
+    /// [`Curses`]: struct.Curses.html
+    /// [`put_styled`]: struct.Window.html#method.put_styled
+    pub fn put_ansi<T: AsRef<str>>(&mut self, text: T, color: &mut Color) -> Result<(), Error> {
+        let text = StyledText::from_ansi(text.as_ref(), color)?;
+        self.put_styled(&text)
     }
 
     /// Delete the character at the point.
@@ -288,26 +491,42 @@ impl Window {
     /// This will shift left the characters on the rest of the line.
     ///
     /// This corresponds to `delch`.
-    pub fn delete_char(&mut self) -> Result<(), ()> {
-        check(self.w.delch())
+    pub fn delete_char(&mut self) -> Result<(), Error> {
+        check("delch", self.handle.w.delch())
     }
     /// Delete the line the point is on.
     ///
     /// This will shift left the characters on the rest of the line.
     ///
     /// This corresponds to `delch`.
-    pub fn delete_line(&mut self) -> Result<(), ()> {
-        check(self.w.deleteln())
+    pub fn delete_line(&mut self) -> Result<(), Error> {
+        check("deleteln", self.handle.w.deleteln())
     }
     /// Delete this `Window`, allowing for error handling outside of
     /// panicking.
-    pub fn delete_window(self) -> Result<(), ()> {
-        check(self.w.delwin())
+    ///
+    /// This fails with [`ErrorKind::WindowInUse`] if this `Window` has sub
+    /// windows that are still alive.  In that case, the `Window` is
+    /// deleted once the last of its sub windows is dropped.
+    ///
+    /// This corresponds to `delwin`.
+    ///
+    /// [`ErrorKind::WindowInUse`]: enum.ErrorKind.html#variant.WindowInUse
+    pub fn delete_window(self) -> Result<(), Error> {
+        match Arc::try_unwrap(self.handle) {
+            Ok(Handle { w, parent, .. }) => {
+                let r = check("delwin", w.delwin());
+                drop(parent);
+                r
+            }
+            Err(_) => Err(Error::new(ErrorKind::WindowInUse, "delwin")),
+        }
     }
 
     /// Draw a border around the edges of the `Window`.
     ///
     /// This corresponds to `border`.
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_border<
         LS: Into<Chtype>,
         RS: Into<Chtype>,
@@ -327,17 +546,20 @@ impl Window {
         top_right_corner: TRC,
         bottom_left_corner: BLC,
         bottom_right_corner: BRC,
-    ) -> Result<(), ()> {
-        check(self.w.border(
-            left_side.into(),
-            right_side.into(),
-            top_side.into(),
-            bottom_side.into(),
-            top_left_corner.into(),
-            top_right_corner.into(),
-            bottom_left_corner.into(),
-            bottom_right_corner.into(),
-        ))
+    ) -> Result<(), Error> {
+        check(
+            "border",
+            self.handle.w.border(
+                left_side.into(),
+                right_side.into(),
+                top_side.into(),
+                bottom_side.into(),
+                top_left_corner.into(),
+                top_right_corner.into(),
+                bottom_left_corner.into(),
+                bottom_right_corner.into(),
+            ),
+        )
     }
     /// Drow a box around the edges of the `Window`.
     ///
@@ -350,8 +572,102 @@ impl Window {
         &mut self,
         vertical: VT,
         horizontal: HT,
-    ) -> Result<(), ()> {
-        check(self.w.draw_box(vertical.into(), horizontal.into()))
+    ) -> Result<(), Error> {
+        check(
+            "box",
+            self.handle.w.draw_box(vertical.into(), horizontal.into()),
+        )
+    }
+    /// Draw a border around the edges of `rect`.
+    ///
+    /// This is [`draw_border`] for a rectangle of the `Window` rather
+    /// than the whole `Window`.  `rect` must be at least two rows and two
+    /// columns.  The point is not moved.
+    ///
+    /// This corresponds to `border` on a derived window of `rect`.
+    ///
+    /// [`draw_border`]: struct.Window.html#method.draw_border
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_border_rect<
+        R: Into<Rect>,
+        LS: Into<Chtype>,
+        RS: Into<Chtype>,
+        TS: Into<Chtype>,
+        BS: Into<Chtype>,
+        TLC: Into<Chtype>,
+        TRC: Into<Chtype>,
+        BLC: Into<Chtype>,
+        BRC: Into<Chtype>,
+    >(
+        &mut self,
+        rect: R,
+        left_side: LS,
+        right_side: RS,
+        top_side: TS,
+        bottom_side: BS,
+        top_left_corner: TLC,
+        top_right_corner: TRC,
+        bottom_left_corner: BLC,
+        bottom_right_corner: BRC,
+    ) -> Result<(), Error> {
+        let rect = rect.into();
+        if rect.size.rows < 2 || rect.size.columns < 2 {
+            return Err(Error::new(ErrorKind::InvalidArgument, "border")
+                .with_arguments(format!("{:?}", rect)));
+        }
+        let mut window = self.create_derived_window(rect.origin, rect.size)?;
+        window.draw_border(
+            left_side,
+            right_side,
+            top_side,
+            bottom_side,
+            top_left_corner,
+            top_right_corner,
+            bottom_left_corner,
+            bottom_right_corner,
+        )?;
+        window.sync_up();
+        Ok(())
+    }
+    /// Draw `border` around the edges of the `Window`.
+    ///
+    /// Unlike [`draw_border`], the border is given as a [`BorderStyle`]
+    /// such as `BorderStyle::Rounded`, optionally with a title and
+    /// footer (see [`Border`]).  The point is not moved.
+    ///
+    /// This corresponds to `wborder_set` with the `wide` feature and
+    /// `border` otherwise.
+    ///
+    /// [`draw_border`]: struct.Window.html#method.draw_border
+    /// [`BorderStyle`]: enum.BorderStyle.html
+    /// [`Border`]: struct.Border.html
+    pub fn draw_styled_border<B: Into<Border>>(&mut self, border: B) -> Result<(), Error> {
+        border::draw(self, &border.into())
+    }
+    /// Draw `border` around the edges of `rect`.
+    ///
+    /// This is [`draw_styled_border`] for a rectangle of the `Window`
+    /// rather than the whole `Window`.  `rect` must be at least two rows
+    /// and two columns.  The point is not moved.
+    ///
+    /// This corresponds to `wborder_set` with the `wide` feature and
+    /// `border` otherwise, on a derived window of `rect`.
+    ///
+    /// [`draw_styled_border`]: struct.Window.html#method.draw_styled_border
+    pub fn draw_styled_border_rect<R: Into<Rect>, B: Into<Border>>(
+        &mut self,
+        rect: R,
+        border: B,
+    ) -> Result<(), Error> {
+        let rect = rect.into();
+        if rect.size.rows < 2 || rect.size.columns < 2 {
+            return Err(Error::new(ErrorKind::InvalidArgument, "border")
+                .with_arguments(format!("{:?}", rect)));
+        }
+        let mut window = self.create_derived_window(rect.origin, rect.size)?;
+        border::draw(&mut window, &border.into())?;
+        window.sync_up();
+        Ok(())
     }
     /// Draw a horizontal line starting at the point.
     ///
@@ -360,8 +676,8 @@ impl Window {
         &mut self,
         ch: T,
         max_length: i32,
-    ) -> Result<(), ()> {
-        check(self.w.hline(ch.into(), max_length))
+    ) -> Result<(), Error> {
+        check("hline", self.handle.w.hline(ch.into(), max_length))
     }
     /// Draw a vertical line starting at the point.
     ///
@@ -370,21 +686,21 @@ impl Window {
         &mut self,
         ch: T,
         max_length: i32,
-    ) -> Result<(), ()> {
-        check(self.w.vline(ch.into(), max_length))
+    ) -> Result<(), Error> {
+        check("vline", self.handle.w.vline(ch.into(), max_length))
     }
 
     /// Test if `p` is enclosed in this `Window`.
     pub fn encloses<P: Into<Point>>(&self, p: P) -> bool {
         let p = p.into();
-        self.w.enclose(p.y, p.x)
+        self.handle.w.enclose(p.y, p.x)
     }
 
     /// Get the start of the `Window` on the physical screen.
     ///
     /// This corresponds to `get_beg_yx`.
     pub fn beginning(&self) -> Point {
-        self.w.get_beg_yx().into()
+        self.handle.w.get_beg_yx().into()
     }
     /// Get the ending of the `Window` on the physical screen.
     pub fn ending(&self) -> Point {
@@ -397,14 +713,21 @@ impl Window {
     ///
     /// This corresponds to `get_cur_yx`.
     pub fn point(&self) -> Point {
-        self.w.get_cur_yx().into()
+        let _cursor = self.handle.lock_cursor();
+        self.handle.w.get_cur_yx().into()
     }
 
     /// Get the size of the `Window`.
     ///
     /// This corresponds to `get_max_yx`.
     pub fn size(&self) -> Dimension {
-        self.w.get_max_yx().into()
+        self.handle.w.get_max_yx().into()
+    }
+    /// Get the [`Rect`] the `Window` covers on the physical screen.
+    ///
+    /// [`Rect`]: struct.Rect.html
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.beginning(), self.size())
     }
 
     /// Insert `n` blank lines above the cursor.
@@ -419,8 +742,8 @@ impl Window {
     /// The point remains the same after this operation.
     ///
     /// This corresponds to `insdelln`.
-    pub fn insert_lines(&mut self, n: i32) -> Result<(), ()> {
-        check(self.w.insdelln(n))
+    pub fn insert_lines(&mut self, n: i32) -> Result<(), Error> {
+        check("insdelln", self.handle.w.insdelln(n))
     }
     /// Insert a blank line above the current line.
     ///
@@ -429,8 +752,8 @@ impl Window {
     /// The point remains the same after this operation.
     ///
     /// This corresponds to `insertln`.
-    pub fn insert_line(&mut self) -> Result<(), ()> {
-        check(self.w.insertln())
+    pub fn insert_line(&mut self) -> Result<(), Error> {
+        check("insertln", self.handle.w.insertln())
     }
     /// Insert a character into the current line.
     ///
@@ -440,8 +763,8 @@ impl Window {
     /// The point remains the same after this operation.
     ///
     /// This corresponds to `insch`.
-    pub fn insert_char<T: Into<Chtype>>(&self, ch: T) -> Result<(), ()> {
-        check(self.w.insch(ch.into()))
+    pub fn insert_char<T: Into<Chtype>>(&self, ch: T) -> Result<(), Error> {
+        check("insch", self.handle.w.insch(ch.into()))
     }
 
     /// Transform the point `p` from `Window`-relative to screen-relative.
@@ -449,22 +772,22 @@ impl Window {
     /// This corresponds to `mouse_trafo`.
     pub fn window_to_screen<P: Into<Point>>(&self, p: P) -> Point {
         let p = p.into();
-        self.w.mouse_trafo(p.y, p.x, true).into()
+        self.handle.w.mouse_trafo(p.y, p.x, true).into()
     }
     /// Transform the point `p` from screen-relative to `Window`-relative.
     ///
     /// This corresponds to `mouse_trafo`.
     pub fn screen_to_window<P: Into<Point>>(&self, p: P) -> Point {
         let p = p.into();
-        self.w.mouse_trafo(p.y, p.x, false).into()
+        self.handle.w.mouse_trafo(p.y, p.x, false).into()
     }
 
     /// Move to the point to `p`.
     ///
     /// This corresponds to `mv`.
-    pub fn move_to<P: Into<Point>>(&mut self, p: P) -> Result<(), ()> {
+    pub fn move_to<P: Into<Point>>(&mut self, p: P) -> Result<(), Error> {
         let p = p.into();
-        check(self.w.mv(p.y, p.x))
+        self.check_point("move", self.handle.w.mv(p.y, p.x), p)
     }
     /// Move to the point `p` then put `ch` at that point.
     ///
@@ -473,9 +796,9 @@ impl Window {
         &mut self,
         p: P,
         ch: T,
-    ) -> Result<(), ()> {
+    ) -> Result<(), Error> {
         let p = p.into();
-        check(self.w.mvaddch(p.y, p.x, ch.into()))
+        self.check_point("mvaddch", self.handle.w.mvaddch(p.y, p.x, ch.into()), p)
     }
     /// Move to the point `p` then put `string` at that point.
     ///
@@ -484,9 +807,82 @@ impl Window {
         &mut self,
         p: P,
         string: T,
-    ) -> Result<(), ()> {
+    ) -> Result<(), Error> {
         let p = p.into();
-        check(self.w.mvaddstr(p.y, p.x, string))
+        self.check_point("mvaddstr", self.handle.w.mvaddstr(p.y, p.x, string), p)
+    }
+    /// Put `text` in `rect`, breaking and aligning its lines as described
+    /// by `options`.
+    ///
+    /// Unlike [`put_str`], lines are broken at the edge of `rect` instead
+    /// of the `Window`, and text that doesn't fit in `rect` is cut off
+    /// rather than scrolling the `Window`.  Widths are measured in
+    /// columns on the screen; with the `wide` feature, East Asian wide
+    /// characters take two.
+    ///
+    /// Returns the number of lines used.
+    ///
+    /// This fails with [`ErrorKind::OutOfBounds`] if `rect` isn't inside
+    /// of the `Window`.
+    ///
+    /// This corresponds to `mvaddstr`.
+    ///
+    /// [`put_str`]: struct.Window.html#method.put_str
+    /// [`ErrorKind::OutOfBounds`]: enum.ErrorKind.html#variant.OutOfBounds
+    pub fn put_wrapped<R: Into<Rect>, T: AsRef<str>>(
+        &mut self,
+        rect: R,
+        text: T,
+        options: &WrapOptions,
+    ) -> Result<i32, Error> {
+        let rect = rect.into();
+        self.check_region("mvaddstr", rect.origin, rect.size)?;
+        if rect.is_empty() {
+            return Ok(0);
+        }
+        let lines = wrap::layout(
+            text.as_ref(),
+            rect.size.columns as usize,
+            rect.size.rows as usize,
+            options,
+        );
+        let size = self.size();
+        for (row, line) in lines.iter().enumerate() {
+            let p = rect.origin
+                + Point {
+                    y: row as i32,
+                    x: line.offset as i32,
+                };
+            let end = p.x + wrap::display_width(&line.text) as i32;
+            match line.text.chars().last() {
+                // Putting a character in the bottom right corner fails
+                // (or scrolls) because the cursor can't move past it,
+                // so insert that one instead.
+                Some(last) if p.y == size.rows - 1 && end == size.columns => {
+                    let (init, _) = line.text.split_at(line.text.len() - last.len_utf8());
+                    self.move_put_str(p, init)?;
+                    let last_point = Point {
+                        y: p.y,
+                        x: end - wrap::char_width(last) as i32,
+                    };
+                    if last.is_ascii() {
+                        self.move_to(last_point)?;
+                        self.insert_char(last)?;
+                    } else if let Err(e) =
+                        self.move_put_str(last_point, last.encode_utf8(&mut [0; 4]))
+                    {
+                        // Curses reports an error after putting the
+                        // character since the cursor can't follow it, so
+                        // only fail if the character isn't there.
+                        if self.read_cell(last_point).character != last {
+                            return Err(e);
+                        }
+                    }
+                }
+                _ => self.move_put_str(p, &line.text)?,
+            }
+        }
+        Ok(lines.len() as i32)
     }
     /// Move to the point `p` then change the attributes of `n` characters after that point.
     ///
@@ -496,24 +892,160 @@ impl Window {
         p: P,
         n: N,
         attributes: T,
-        color_pair: i16,
-    ) -> Result<(), ()> {
+        color_pair: ColorPairId,
+    ) -> Result<(), Error> {
         let p = p.into();
-        check(self.w.mvchgat(
+        let r = self.handle.w.mvchgat(
             p.y,
             p.x,
             n.into().unwrap_number_or(-1),
             attributes.into(),
-            color_pair,
-        ))
+            color_pair.number(),
+        );
+        self.check_point("mvchgat", r, p)
     }
     /// Move to `p` then get the character at the point.
     ///
+    /// To read characters without moving the cursor, see [`cell_at`].
+    ///
     /// This corresponds to `mvinch`.
+    ///
+    /// [`cell_at`]: struct.Window.html#method.cell_at
     pub fn move_get_char<P: Into<Point>>(&mut self, p: P) -> Chtype {
         let p = p.into();
-        self.w.mvinch(p.y, p.x)
+        self.handle.w.mvinch(p.y, p.x)
+    }
+    /// Get the [`Cell`] at `p`.
+    ///
+    /// Curses reads at the cursor, so the cursor is moved to `p` and then
+    /// back to where it was.  This is done under a lock, so other threads
+    /// reading the `Window` never see it moved.
+    ///
+    /// This fails with [`ErrorKind::OutOfBounds`] if `p` is outside of
+    /// the `Window`.
+    ///
+    /// This corresponds to `mvinch`, or to `mvin_wch` with the `wide`
+    /// feature.
+    ///
+    /// [`Cell`]: struct.Cell.html
+    /// [`ErrorKind::OutOfBounds`]: enum.ErrorKind.html#variant.OutOfBounds
+    pub fn cell_at<P: Into<Point>>(&self, p: P) -> Result<Cell, Error> {
+        let p = p.into();
+        self.check_region(
+            "mvinch",
+            p,
+            Dimension {
+                rows: 1,
+                columns: 1,
+            },
+        )?;
+        Ok(self.read_cell(p))
+    }
+    /// Get an iterator over the [`Cell`]s in the rectangle of `size`
+    /// starting at `start`, row by row.
+    ///
+    /// Each `Cell` is read as by [`cell_at`], so the cursor is left where
+    /// it was.
+    ///
+    /// This fails with [`ErrorKind::OutOfBounds`] if the rectangle is not
+    /// inside of the `Window`.
+    ///
+    /// [`Cell`]: struct.Cell.html
+    /// [`cell_at`]: struct.Window.html#method.cell_at
+    /// [`ErrorKind::OutOfBounds`]: enum.ErrorKind.html#variant.OutOfBounds
+    pub fn cells<P: Into<Point>, D: Into<Dimension>>(
+        &self,
+        start: P,
+        size: D,
+    ) -> Result<Cells<'_>, Error> {
+        let start = start.into();
+        let size = size.into();
+        self.check_region("mvinch", start, size)?;
+        Ok(Cells::new(self, start, size))
+    }
+    /// Get the characters on the line `row`.
+    ///
+    /// Each position of the line is one character of the `String`, so
+    /// trailing blanks are included and a character that takes up two
+    /// columns is repeated.  The `Cell`s are read as by [`cell_at`], so
+    /// the cursor is left where it was.
+    ///
+    /// This fails with [`ErrorKind::OutOfBounds`] if `row` is outside of
+    /// the `Window`.
+    ///
+    /// [`cell_at`]: struct.Window.html#method.cell_at
+    /// [`ErrorKind::OutOfBounds`]: enum.ErrorKind.html#variant.OutOfBounds
+    pub fn line_text(&self, row: i32) -> Result<String, Error> {
+        let size = Dimension {
+            rows: 1,
+            columns: self.size().columns,
+        };
+        let cells = self.cells((row, 0), size)?;
+        Ok(cells.map(|(_, cell)| cell.character).collect())
+    }
+    /// Get the characters on every line of the `Window`.
+    ///
+    /// See [`line_text`].
+    ///
+    /// [`line_text`]: struct.Window.html#method.line_text
+    pub fn text(&self) -> Vec<String> {
+        (0..self.size().rows)
+            .filter_map(|row| self.line_text(row).ok())
+            .collect()
+    }
+
+    /// Read the `Cell` at `p`, which must be inside of the `Window`,
+    /// and restore the cursor.
+    ///
+    /// Curses can only read at the cursor, so it is moved to `p` and
+    /// back while the cursor is locked.
+    pub(crate) fn read_cell(&self, p: Point) -> Cell {
+        let _cursor = self.handle.lock_cursor();
+        let (y, x) = self.handle.w.get_cur_yx();
+        let cell = self.read_cell_here(p);
+        self.handle.w.mv(y, x);
+        cell
+    }
+
+    #[cfg(all(unix, feature = "wide"))]
+    fn read_cell_here(&self, p: Point) -> Cell {
+        match self.handle.w.mvin_wch(p.y, p.x) {
+            Some((character, attributes, pair)) => Cell {
+                character,
+                attributes: attributes & A_ATTRIBUTES & !A_COLOR,
+                color_pair: ColorPairId::new(pair),
+            },
+            None => Cell::from(self.handle.w.mvinch(p.y, p.x)),
+        }
+    }
+    #[cfg(not(all(unix, feature = "wide")))]
+    fn read_cell_here(&self, p: Point) -> Cell {
+        Cell::from(self.handle.w.mvinch(p.y, p.x))
     }
+
+    /// Check that the rectangle of `size` starting at `start` is inside
+    /// of the `Window`.
+    fn check_region(
+        &self,
+        function: &'static str,
+        start: Point,
+        size: Dimension,
+    ) -> Result<(), Error> {
+        let max = self.size();
+        if start.y < 0
+            || start.x < 0
+            || size.rows < 0
+            || size.columns < 0
+            || start.y + size.rows > max.rows
+            || start.x + size.columns > max.columns
+        {
+            Err(Error::new(ErrorKind::OutOfBounds, function)
+                .with_arguments(format!("{:?}, {:?}", start, size)))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Move to `p` then insert the character at the point.
     ///
     /// This corresponds to `mvinsch`.
@@ -521,23 +1053,42 @@ impl Window {
         &mut self,
         p: P,
         ch: T,
-    ) -> Result<(), ()> {
+    ) -> Result<(), Error> {
+        let p = p.into();
+        self.check_point("mvinsch", self.handle.w.mvinsch(p.y, p.x, ch.into()), p)
+    }
+    /// Change which part of the parent `Window` this derived `Window`
+    /// shows.
+    ///
+    /// `p` is relative to the parent `Window`.  The position of this
+    /// `Window` on the screen is not changed.
+    ///
+    /// This corresponds to `mvderwin`.
+    pub fn move_derived_window<P: Into<Point>>(&mut self, p: P) -> Result<(), Error> {
         let p = p.into();
-        check(self.w.mvinsch(p.y, p.x, ch.into()))
-    }
-    // /// Move to `p` then insert the character at the point.
-    // ///
-    // /// This corresponds to `mvinsch`.
-    // pub fn move_derived_window<P: Into<Point>>(&mut self, p: P) -> Result<(), ()> {
-    //     let p = p.into();
-    //     check(self.w.mvderwin(p.y, p.x))
-    // }
+        check("mvderwin", self.handle.w.mvderwin(p.y, p.x))
+            .map_err(|e| e.with_arguments(format!("{:?}", p)))
+    }
     /// Move the `Window` such that it starts at `p` on the screen.
     ///
     /// This corresponds to `mvwin`.
-    pub fn move_window<P: Into<Point>>(&mut self, p: P) -> Result<(), ()> {
+    pub fn move_window<P: Into<Point>>(&mut self, p: P) -> Result<(), Error> {
         let p = p.into();
-        check(self.w.mvwin(p.y, p.x))
+        check("mvwin", self.handle.w.mvwin(p.y, p.x))
+            .map_err(|e| e.with_arguments(format!("{:?}", p)))
+    }
+
+    /// Change the size of the `Window`.
+    ///
+    /// The start of the `Window` stays the same.  Text outside of the new
+    /// size is lost and new space is filled with the background.  Sub
+    /// windows cannot be resized beyond their parent.
+    ///
+    /// This corresponds to `wresize`.
+    pub fn resize<D: Into<Dimension>>(&mut self, size: D) -> Result<(), Error> {
+        let size = size.into();
+        let r = sys::wresize(self.as_ptr(), size.rows, size.columns);
+        check("wresize", r).map_err(|e| e.with_arguments(format!("{:?}", size)))
     }
 
     /// Read a key event from the `Window`.
@@ -570,7 +1121,92 @@ impl Window {
     /// [`Curses::set_input_buffering_mode`]: struct.Curses.html#method.set_input_buffering_mode
     /// [`Curses::set_echo_input`]: struct.Curses.html#method.set_echo_input
     pub fn read_char(&mut self) -> Option<Input> {
-        self.w.getch()
+        self.handle.w.getch()
+    }
+    /// Read a key event from the `Window` as a [`KeyEvent`].
+    ///
+    /// This behaves like [`read_char`] but normalizes the many ways
+    /// terminals report modifier keys.  Control characters are reported as
+    /// the key with the `Control` modifier, modified special keys (such as
+    /// `kLFT5`) are reported as the key with the appropriate modifiers, and
+    /// an `Escape` immediately followed by another key is reported as
+    /// that key with the `Alt` modifier.
+    ///
+    /// Returns `None` if no input is available (see [`set_block_on_read`]
+    /// and [`set_timeout`]).
+    ///
+    /// [`KeyEvent`]: struct.KeyEvent.html
+    /// [`read_char`]: struct.Window.html#method.read_char
+    /// [`set_block_on_read`]: struct.Window.html#method.set_block_on_read
+    /// [`set_timeout`]: struct.Window.html#method.set_timeout
+    pub fn read_key(&mut self) -> Option<KeyEvent> {
+        let event = self.read_single_key()?;
+        if event == KeyEvent::from(Key::Escape) {
+            let delay = sys::get_delay(self.as_ptr());
+            sys::wtimeout(self.as_ptr(), 0);
+            let next = self.read_single_key();
+            sys::wtimeout(self.as_ptr(), delay);
+            if let Some(next) = next {
+                return Some(next.with_modifiers(Modifiers::ALT));
+            }
+        }
+        Some(event)
+    }
+    /// Read one key without treating `Escape` as a prefix.
+    ///
+    /// `pancurses` decodes some key codes incorrectly (for example
+    /// `KEY_F(16)` and above) so key codes are decoded here.
+    #[cfg(unix)]
+    fn read_single_key(&mut self) -> Option<KeyEvent> {
+        let code = sys::wgetch(self.as_ptr());
+        if code < 0 {
+            return None;
+        }
+        if code < KEY_OFFSET {
+            return Some(KeyEvent::from_char(self.decode_utf8(code as u8)));
+        }
+        let function_key = code - (KEY_OFFSET + 8);
+        if (0..64).contains(&function_key) {
+            return Some(KeyEvent::from_function_key(function_key));
+        }
+        let index = if code <= KEY_F15 {
+            code - KEY_OFFSET
+        } else {
+            code - KEY_OFFSET - 48
+        };
+        match SPECIAL_KEY_CODES.get(index as usize) {
+            Some(input) => Some(KeyEvent::from(*input)),
+            None => Some(KeyEvent::from_unknown(code)),
+        }
+    }
+    /// Read the rest of a UTF-8 encoded character starting with `first`.
+    #[cfg(unix)]
+    fn decode_utf8(&mut self, first: u8) -> char {
+        let mut bytes = vec![first];
+        let length = match first {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+        while bytes.len() < length {
+            let code = sys::wgetch(self.as_ptr());
+            if !(0x80..0xc0).contains(&code) {
+                if code >= 0 {
+                    sys::ungetch(code);
+                }
+                break;
+            }
+            bytes.push(code as u8);
+        }
+        std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(first as char)
+    }
+    #[cfg(windows)]
+    fn read_single_key(&mut self) -> Option<KeyEvent> {
+        self.read_char().map(KeyEvent::from)
     }
     /// Place `input` into the front of the input queue.
     ///
@@ -579,8 +1215,8 @@ impl Window {
     /// This corresponds to `ungetch`.
     ///
     /// [`read_char`]: struct.Window.html#method.read_char
-    pub fn unread_char(&mut self, input: &Input) -> Result<(), ()> {
-        check(self.w.ungetch(input))
+    pub fn unread_char(&mut self, input: &Input) -> Result<(), Error> {
+        check("ungetch", sys::unget_input(input))
     }
     /// Set whether [`read_char`] will block until an input is ready.
     ///
@@ -591,8 +1227,8 @@ impl Window {
     /// if there is no input to yield.
     ///
     /// This corresponds to `nodelay(!block)`.
-    pub fn set_block_on_read(&mut self, block: bool) -> Result<(), ()> {
-        check(self.w.nodelay(!block))
+    pub fn set_block_on_read(&mut self, block: bool) -> Result<(), Error> {
+        check("nodelay", self.handle.w.nodelay(!block))
     }
     /// [`read_char`] will block for at most `duration` and wait for input.
     ///
@@ -611,7 +1247,7 @@ impl Window {
     /// [`Curses::set_timeout`]: struct.Curses.html#method.set_timeout
     /// [`Curses`]: struct.Curses.html
     pub fn set_timeout(&self, duration: Option<Duration>) {
-        self.w.timeout(duration.map(as_millis).unwrap_or(-1))
+        self.handle.w.timeout(duration.map(as_millis).unwrap_or(-1))
     }
     /// Enable or disable function key interpolation.
     ///
@@ -628,15 +1264,15 @@ impl Window {
     /// This corresponds to `keypad`.
     ///
     /// [`read_char`]: struct.Window.html#method.read_char
-    pub fn read_interpolate_function_keys(&mut self, interpolate: bool) -> Result<(), ()> {
-        check(self.w.keypad(interpolate))
+    pub fn read_interpolate_function_keys(&mut self, interpolate: bool) -> Result<(), Error> {
+        check("keypad", self.handle.w.keypad(interpolate))
     }
 
     /// Copy this `Window` to the physical screen.
     ///
     /// This corresponds to `wrefresh`.
-    pub fn refresh(&mut self) -> Result<(), ()> {
-        check(self.w.refresh())
+    pub fn refresh(&mut self) -> Result<(), Error> {
+        check("refresh", self.handle.w.refresh())
     }
     /// Refresh the virtual screen.
     ///
@@ -648,23 +1284,23 @@ impl Window {
     /// This corresponds to `wnoutrefresh`.
     ///
     /// [`Curses::update`]: struct.Curses.html#method.update
-    pub fn refresh_virtual_screen(&mut self) -> Result<(), ()> {
-        check(self.w.noutrefresh())
+    pub fn refresh_virtual_screen(&mut self) -> Result<(), Error> {
+        check("noutrefresh", self.handle.w.noutrefresh())
     }
     /// Make the next call to [`refresh`] clear and then rerender.
     ///
     /// This corresponds to `clearok`.
     ///
     /// [`refresh`]: struct.Window.html#method.refresh
-    pub fn refresh_force_clear(&mut self, force_clear: bool) -> Result<(), ()> {
-        check(self.w.clearok(force_clear))
+    pub fn refresh_force_clear(&mut self, force_clear: bool) -> Result<(), Error> {
+        check("clearok", self.handle.w.clearok(force_clear))
     }
 
     /// Enable or disable scrolling.
     ///
     /// This corresponds to `scrollok`.
-    pub fn set_scroll_enabled(&mut self, scroll: bool) -> Result<(), ()> {
-        check(self.w.scrollok(scroll))
+    pub fn set_scroll_enabled(&mut self, scroll: bool) -> Result<(), Error> {
+        check("scrollok", self.handle.w.scrollok(scroll))
     }
     /// Set a software scrolling region.
     ///
@@ -674,26 +1310,121 @@ impl Window {
     /// This corresponds to `setscrreg`.
     ///
     /// [`set_scroll_enabled`]: struct.Window.html#method.set_scroll_enabled
-    pub fn set_scroll_region(&mut self, start: i32, end: i32) -> Result<(), ()> {
-        check(self.w.setscrreg(start, end))
+    pub fn set_scroll_region(&mut self, start: i32, end: i32) -> Result<(), Error> {
+        check("setscrreg", self.handle.w.setscrreg(start, end))
     }
 
     /// Create a new window
     ///
+    /// The new `Window` shares memory with this `Window`.  To create a
+    /// `Window` with its own memory, see [`Curses::create_window`].
+    ///
     /// This corresponds to `subwin`.  Note that the arguments have been
     /// reordered to be more consistent with other functions.
+    ///
+    /// [`Curses::create_window`]: struct.Curses.html#method.create_window
     pub fn create_sub_window<P: Into<Point>, D: Into<Dimension>>(
         &self,
         point: P,
         size: D,
-    ) -> Result<Window, ()> {
+    ) -> Result<Window, Error> {
+        let p = point.into();
+        let d = size.into();
+        match self.handle.w.subwin(d.rows, d.columns, p.y, p.x) {
+            Some(w) => Ok(Window::with_parent(w, self)),
+            None => {
+                let beginning = self.beginning();
+                let ending = self.ending();
+                let kind = if p.y < beginning.y
+                    || p.x < beginning.x
+                    || p.y + d.rows > ending.y
+                    || p.x + d.columns > ending.x
+                {
+                    ErrorKind::OutOfBounds
+                } else {
+                    ErrorKind::Failed
+                };
+                Err(Error::new(kind, "subwin").with_arguments(format!("{:?}, {:?}", p, d)))
+            }
+        }
+    }
+    /// Create a new window relative to this `Window`.
+    ///
+    /// This is the same as [`create_sub_window`] except that `point` is
+    /// relative to the start of this `Window` instead of the screen.
+    ///
+    /// This corresponds to `derwin`.  Note that the arguments have been
+    /// reordered to be more consistent with other functions.
+    ///
+    /// [`create_sub_window`]: struct.Window.html#method.create_sub_window
+    pub fn create_derived_window<P: Into<Point>, D: Into<Dimension>>(
+        &self,
+        point: P,
+        size: D,
+    ) -> Result<Window, Error> {
         let p = point.into();
         let d = size.into();
-        match self.w.subwin(d.rows, d.columns, p.y, p.x) {
-            Ok(w) => Ok(Window { w }),
-            Err(_) => Err(()),
+        match self.handle.w.derwin(d.rows, d.columns, p.y, p.x) {
+            Some(w) => Ok(Window::with_parent(w, self)),
+            None => {
+                let size = self.size();
+                let kind = if p.y < 0
+                    || p.x < 0
+                    || p.y + d.rows > size.rows
+                    || p.x + d.columns > size.columns
+                {
+                    ErrorKind::OutOfBounds
+                } else {
+                    ErrorKind::Failed
+                };
+                Err(Error::new(kind, "derwin").with_arguments(format!("{:?}, {:?}", p, d)))
+            }
         }
     }
+    /// Create a new window covering `rect` of the screen.
+    ///
+    /// This is [`create_sub_window`] with the position and size given as
+    /// a [`Rect`].
+    ///
+    /// This corresponds to `subwin`.
+    ///
+    /// [`create_sub_window`]: struct.Window.html#method.create_sub_window
+    /// [`Rect`]: struct.Rect.html
+    pub fn create_sub_window_rect<R: Into<Rect>>(&self, rect: R) -> Result<Window, Error> {
+        let rect = rect.into();
+        self.create_sub_window(rect.origin, rect.size)
+    }
+
+    /// Enable or disable automatically calling [`sync_up`] whenever this
+    /// `Window` is changed.
+    ///
+    /// This corresponds to `syncok`.
+    ///
+    /// [`sync_up`]: struct.Window.html#method.sync_up
+    pub fn set_sync_enabled(&mut self, sync: bool) -> Result<(), Error> {
+        check("syncok", sys::syncok(self.as_ptr(), sync))
+    }
+    /// Mark the parents of this `Window` as changed wherever this
+    /// `Window` has changed.
+    ///
+    /// This corresponds to `wsyncup`.
+    pub fn sync_up(&mut self) {
+        sys::wsyncup(self.as_ptr())
+    }
+    /// Mark this `Window` as changed wherever any of its parents have
+    /// changed.
+    ///
+    /// This corresponds to `wsyncdown`.
+    pub fn sync_down(&mut self) {
+        sys::wsyncdown(self.as_ptr())
+    }
+    /// Update the point of the parents of this `Window` to match the
+    /// point of this `Window`.
+    ///
+    /// This corresponds to `wcursyncup`.
+    pub fn sync_cursor_up(&mut self) {
+        sys::wcursyncup(self.as_ptr())
+    }
 
     /// Test if this `Window` has been modified since the last call to
     /// [`refresh`].
@@ -702,7 +1433,7 @@ impl Window {
     ///
     /// [`refresh`]: struct.Window.html#method.refresh
     pub fn touched(&self) -> bool {
-        self.w.is_touched()
+        self.handle.w.is_touched()
     }
     /// Test if the specified line has been modified since the last call to
     /// [`refresh`].
@@ -711,7 +1442,7 @@ impl Window {
     ///
     /// [`refresh`]: struct.Window.html#method.refresh
     pub fn line_touched(&self, line: i32) -> bool {
-        self.w.is_linetouched(line)
+        self.handle.w.is_linetouched(line)
     }
     /// Force the entire `Window` to be redrawn upon the next call to
     /// [`refresh`].
@@ -719,8 +1450,8 @@ impl Window {
     /// This corresponds to `touchwin`.
     ///
     /// [`refresh`]: struct.Window.html#method.refresh
-    pub fn touch(&mut self) -> Result<(), ()> {
-        check(self.w.touch())
+    pub fn touch(&mut self) -> Result<(), Error> {
+        check("touchwin", self.handle.w.touch())
     }
     /// Force the specified lines to be redrawn upon the next call to
     /// [`refresh`].
@@ -728,8 +1459,8 @@ impl Window {
     /// This corresponds to `touchline`.
     ///
     /// [`refresh`]: struct.Window.html#method.refresh
-    pub fn touch_lines(&mut self, start: i32, count: i32) -> Result<(), ()> {
-        check(self.w.touchline(start, count))
+    pub fn touch_lines(&mut self, start: i32, count: i32) -> Result<(), Error> {
+        check("touchline", self.handle.w.touchline(start, count))
     }
     /// Pretend this `Window` hasn't changed and thus won't redraw it upon the
     /// next call to [`refresh`].
@@ -737,8 +1468,8 @@ impl Window {
     /// This corresponds to `touchline`.
     ///
     /// [`refresh`]: struct.Window.html#method.refresh
-    pub fn untouch(&mut self) -> Result<(), ()> {
-        check(self.w.untouch())
+    pub fn untouch(&mut self) -> Result<(), Error> {
+        check("untouchwin", self.handle.w.untouch())
     }
     /// Pretend the specified lines haven't changed and thus won't redraw it
     /// upon the next call to [`refresh`].
@@ -746,19 +1477,47 @@ impl Window {
     /// This corresponds to `touchline`.
     ///
     /// [`refresh`]: struct.Window.html#method.refresh
-    pub fn untouch_lines(&mut self, start: i32, count: i32) -> Result<(), ()> {
-        check(self.w.touchln(start, count, false))
+    pub fn untouch_lines(&mut self, start: i32, count: i32) -> Result<(), Error> {
+        check("touchln", self.handle.w.touchln(start, count, false))
+    }
+}
+
+/// Identifies a [`Window`] without keeping it alive.
+///
+/// The `Window` can't be accessed through a `WeakWindow`; it is only
+/// compared against.
+///
+/// [`Window`]: struct.Window.html
+pub(crate) struct WeakWindow {
+    handle: Weak<Handle>,
+}
+
+impl WeakWindow {
+    /// Test if the `Window` is still alive.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.handle.strong_count() > 0
+    }
+
+    /// Test if this refers to `window`.
+    pub(crate) fn refers_to(&self, window: &Window) -> bool {
+        std::ptr::eq(self.handle.as_ptr(), Arc::as_ptr(&window.handle))
     }
 }
 
 /// Duplicate this `Window`.
 ///
 /// This corresponds to `dupwin`.
+///
+/// # Panics
+///
+/// Panics if curses fails to allocate the copy.
 impl Clone for Window {
     fn clone(&self) -> Self {
-        Window { w: self.w.dupwin() }
+        Window {
+            handle: Arc::new(Handle::new(
+                self.handle.w.dupwin().expect("dupwin failed"),
+                self.handle.parent.clone(),
+            )),
+        }
     }
 }
-
-unsafe impl Send for Window {}
-unsafe impl Sync for Window {}
@@ -0,0 +1,406 @@
+// `iter::repeat_n` needs a newer Rust than this crate supports.
+#![allow(clippy::manual_repeat_n)]
+
+#[cfg(feature = "wide")]
+use unicode_width::UnicodeWidthChar;
+
+/// How text that is wider than its area is broken into lines.
+///
+/// See [`WrapOptions`].
+///
+/// [`WrapOptions`]: struct.WrapOptions.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WrapMode {
+    /// Break lines between words.  Words that are wider than the area
+    /// are broken between characters.
+    Word,
+    /// Break lines between characters, as soon as the line is full.
+    Character,
+    /// Don't break lines; cut off what doesn't fit.
+    None,
+}
+
+/// Where each line of text is placed in its area.
+///
+/// See [`WrapOptions`].
+///
+/// [`WrapOptions`]: struct.WrapOptions.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+    /// Stretch the spaces between words so that every line but the
+    /// last of each paragraph fills the area.  Lines that can't be
+    /// stretched are aligned left.
+    Justify,
+}
+
+/// How [`Window::put_wrapped`] lays out text.
+///
+/// The default wraps between words, aligns left, expands tabs to every
+/// 8 columns, and cuts off text that doesn't fit without an ellipsis.
+///
+/// [`Window::put_wrapped`]: struct.Window.html#method.put_wrapped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WrapOptions {
+    mode: WrapMode,
+    alignment: Alignment,
+    ellipsis: Option<String>,
+    tab_width: usize,
+}
+
+impl Default for WrapOptions {
+    fn default() -> Self {
+        WrapOptions {
+            mode: WrapMode::Word,
+            alignment: Alignment::Left,
+            ellipsis: None,
+            tab_width: 8,
+        }
+    }
+}
+
+impl WrapOptions {
+    /// Create the default `WrapOptions`.
+    pub fn new() -> Self {
+        WrapOptions::default()
+    }
+
+    /// Set how lines are broken.
+    pub fn mode(self, mode: WrapMode) -> Self {
+        WrapOptions { mode, ..self }
+    }
+    /// Set where lines are placed.
+    pub fn alignment(self, alignment: Alignment) -> Self {
+        WrapOptions { alignment, ..self }
+    }
+    /// End text that is cut off with `ellipsis`, such as `"..."`.
+    pub fn ellipsis<T: Into<String>>(self, ellipsis: T) -> Self {
+        WrapOptions {
+            ellipsis: Some(ellipsis.into()),
+            ..self
+        }
+    }
+    /// Expand tabs to every `tab_width` columns.
+    pub fn tab_width(self, tab_width: usize) -> Self {
+        WrapOptions {
+            tab_width: tab_width.max(1),
+            ..self
+        }
+    }
+}
+
+/// Get the number of columns `c` takes on the screen.
+///
+/// Characters are counted as one column unless the `wide` feature is
+/// enabled, in which case East Asian wide characters are two columns and
+/// combining characters are zero.
+pub(crate) fn char_width(c: char) -> usize {
+    #[cfg(feature = "wide")]
+    {
+        c.width().unwrap_or(0)
+    }
+    #[cfg(not(feature = "wide"))]
+    {
+        let _ = c;
+        1
+    }
+}
+
+/// Get the number of columns `s` takes on the screen.  See
+/// [`char_width`].
+pub(crate) fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+/// Cut `s` down to at most `width` columns.
+pub(crate) fn truncate(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices() {
+        used += char_width(c);
+        if used > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// A line of laid out text.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Line {
+    /// The column the line starts at.
+    pub offset: usize,
+    pub text: String,
+}
+
+/// A line produced by wrapping, before it is aligned.
+struct Wrapped {
+    text: String,
+    /// Whether the line was broken to fit, rather than ending its
+    /// paragraph.
+    broken: bool,
+}
+
+/// Lay out `text` in an area of `width` columns and at most `rows`
+/// lines.
+pub(crate) fn layout(text: &str, width: usize, rows: usize, options: &WrapOptions) -> Vec<Line> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let paragraph = expand_tabs(paragraph.trim_end_matches('\r'), options.tab_width);
+        match options.mode {
+            WrapMode::Word => wrap_words(&paragraph, width, &mut lines),
+            WrapMode::Character => wrap_characters(&paragraph, width, &mut lines),
+            WrapMode::None => lines.push(Wrapped {
+                text: paragraph,
+                broken: false,
+            }),
+        }
+    }
+
+    let cut_off = lines.len() > rows;
+    lines.truncate(rows);
+    let last = lines.len().saturating_sub(1);
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut line)| {
+            let overflows = display_width(&line.text) > width;
+            if overflows || (cut_off && i == last) {
+                line.text = with_ellipsis(&line.text, width, options.ellipsis.as_ref());
+                line.broken = false;
+            }
+            align(line, width, options.alignment)
+        })
+        .collect()
+}
+
+fn expand_tabs(s: &str, tab_width: usize) -> String {
+    let mut expanded = String::new();
+    let mut column = 0;
+    for c in s.chars() {
+        if c == '\t' {
+            let spaces = tab_width - column % tab_width;
+            expanded.extend(std::iter::repeat(' ').take(spaces));
+            column += spaces;
+        } else {
+            expanded.push(c);
+            column += char_width(c);
+        }
+    }
+    expanded
+}
+
+fn wrap_characters(paragraph: &str, width: usize, lines: &mut Vec<Wrapped>) {
+    let mut line = String::new();
+    let mut used = 0;
+    for c in paragraph.chars() {
+        let c_width = char_width(c);
+        if used + c_width > width && !line.is_empty() {
+            lines.push(Wrapped {
+                text: std::mem::take(&mut line),
+                broken: true,
+            });
+            used = 0;
+        }
+        line.push(c);
+        used += c_width;
+    }
+    lines.push(Wrapped {
+        text: line,
+        broken: false,
+    });
+}
+
+fn wrap_words(paragraph: &str, width: usize, lines: &mut Vec<Wrapped>) {
+    // Leading blanks indent the first line.
+    let indent = paragraph.len() - paragraph.trim_start_matches(' ').len();
+    let mut line = paragraph[..indent].to_string();
+    let mut used = indent;
+    let mut rest = &paragraph[indent..];
+    while !rest.is_empty() {
+        let spaces = rest.len() - rest.trim_start_matches(' ').len();
+        let word_end = rest[spaces..].find(' ').map_or(rest.len(), |i| i + spaces);
+        let word = &rest[spaces..word_end];
+        rest = &rest[word_end..];
+        if word.is_empty() {
+            break;
+        }
+        let word_width = display_width(word);
+        if used > 0 && used + spaces + word_width <= width {
+            line.extend(std::iter::repeat(' ').take(spaces));
+            line.push_str(word);
+            used += spaces + word_width;
+            continue;
+        }
+        if used > 0 && !line.trim().is_empty() {
+            lines.push(Wrapped {
+                text: std::mem::take(&mut line),
+                broken: true,
+            });
+        }
+        // Break words that don't fit on a line of their own.
+        let mut word = word;
+        while display_width(word) > width && width > 0 {
+            let part = truncate(word, width);
+            let part = if part.is_empty() {
+                &word[..word.chars().next().map_or(0, char::len_utf8)]
+            } else {
+                part
+            };
+            lines.push(Wrapped {
+                text: part.to_string(),
+                broken: true,
+            });
+            word = &word[part.len()..];
+        }
+        line = word.to_string();
+        used = display_width(word);
+    }
+    lines.push(Wrapped {
+        text: line,
+        broken: false,
+    });
+}
+
+/// Cut `s` down to `width` columns, ending it with `ellipsis` if there
+/// is one.
+fn with_ellipsis(s: &str, width: usize, ellipsis: Option<&String>) -> String {
+    match ellipsis {
+        Some(ellipsis) if display_width(ellipsis) <= width => {
+            let kept = truncate(s.trim_end(), width - display_width(ellipsis));
+            format!("{}{}", kept, ellipsis)
+        }
+        _ => truncate(s, width).to_string(),
+    }
+}
+
+fn align(line: Wrapped, width: usize, alignment: Alignment) -> Line {
+    let text = line.text.trim_end().to_string();
+    let free = width.saturating_sub(display_width(&text));
+    match alignment {
+        Alignment::Left => Line { offset: 0, text },
+        Alignment::Center => Line {
+            offset: free / 2,
+            text,
+        },
+        Alignment::Right => Line { offset: free, text },
+        Alignment::Justify if line.broken => Line {
+            offset: 0,
+            text: justify(&text, free),
+        },
+        Alignment::Justify => Line { offset: 0, text },
+    }
+}
+
+/// Add `free` spaces between the words of `text`, favoring the gaps on
+/// the left.
+fn justify(text: &str, free: usize) -> String {
+    let indent = text.len() - text.trim_start().len();
+    let words: Vec<&str> = text[indent..].split_whitespace().collect();
+    if words.len() < 2 {
+        return text.to_string();
+    }
+    // Justified lines are rebuilt with single spaces.
+    let free = free + text[indent..].len()
+        - words.iter().map(|w| w.len()).sum::<usize>()
+        - (words.len() - 1);
+    let gaps = words.len() - 1;
+    let mut justified = text[..indent].to_string();
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            let spaces = 1 + free / gaps + if i <= free % gaps { 1 } else { 0 };
+            justified.extend(std::iter::repeat(' ').take(spaces));
+        }
+        justified.push_str(word);
+    }
+    justified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str, width: usize, rows: usize, options: WrapOptions) -> Vec<(usize, String)> {
+        layout(text, width, rows, &options)
+            .into_iter()
+            .map(|line| (line.offset, line.text))
+            .collect()
+    }
+
+    fn texts(text: &str, width: usize, options: WrapOptions) -> Vec<String> {
+        lines(text, width, 100, options)
+            .into_iter()
+            .map(|(_, text)| text)
+            .collect()
+    }
+
+    #[test]
+    fn word_wrap() {
+        let options = WrapOptions::new();
+        assert_eq!(
+            texts("the quick brown fox jumps", 10, options.clone()),
+            ["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(
+            texts("a verylongword b", 5, options.clone()),
+            ["a", "veryl", "ongwo", "rd b"]
+        );
+        assert_eq!(texts("one\n\ntwo", 10, options), ["one", "", "two"]);
+    }
+
+    #[test]
+    fn character_wrap() {
+        let options = WrapOptions::new().mode(WrapMode::Character);
+        assert_eq!(texts("hello world", 4, options), ["hell", "o wo", "rld"]);
+    }
+
+    #[test]
+    fn no_wrap_truncates() {
+        let options = WrapOptions::new().mode(WrapMode::None).ellipsis("..");
+        assert_eq!(texts("hello world\nhi", 7, options), ["hello..", "hi"]);
+    }
+
+    #[test]
+    fn ellipsis_on_last_row() {
+        let options = WrapOptions::new().ellipsis("...");
+        assert_eq!(
+            lines("one two three four", 9, 1, options),
+            [(0, "one tw...".to_string())]
+        );
+        assert_eq!(
+            lines("one two three four", 9, 1, WrapOptions::new()),
+            [(0, "one two".to_string())]
+        );
+    }
+
+    #[test]
+    fn alignment() {
+        let center = WrapOptions::new().alignment(Alignment::Center);
+        assert_eq!(lines("abc", 8, 1, center), [(2, "abc".to_string())]);
+        let right = WrapOptions::new().alignment(Alignment::Right);
+        assert_eq!(lines("abc", 8, 1, right), [(5, "abc".to_string())]);
+        let justify = WrapOptions::new().alignment(Alignment::Justify);
+        assert_eq!(
+            texts("a bb c dd eee f", 10, justify),
+            ["a  bb c dd", "eee f"]
+        );
+    }
+
+    #[test]
+    fn tabs() {
+        let options = WrapOptions::new().tab_width(4);
+        assert_eq!(texts("a\tb\tc", 20, options), ["a   b   c"]);
+    }
+
+    #[cfg(feature = "wide")]
+    #[test]
+    fn wide_characters() {
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(
+            texts("日本語 です", 6, WrapOptions::new()),
+            ["日本語", "です"]
+        );
+        assert_eq!(texts("日本語", 5, WrapOptions::new()), ["日本", "語"]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Check that `CursesBuilder` applies its options and ends curses again
+//! if one of them fails.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+use std::env;
+use std::io::stdin;
+use std::time::Duration;
+
+#[test]
+fn build_applies_options() {
+    if common::is_child() {
+        let curses = CursesBuilder::new()
+            .panic_hook(true)
+            .color(true)
+            .echo_input(false)
+            .input_buffering_mode(InputBufferingMode::UnbufferedNoSignals)
+            .read_interpolate_function_keys(true)
+            .translate_new_lines(false)
+            .cursor_visibility(CursorVisibility::Invisible)
+            .mouse_mask(ALL_MOUSE_EVENTS)
+            .escape_delay(Duration::from_millis(25))
+            .build()
+            .unwrap();
+        assert!(!common::is_shell_mode(&stdin()));
+        assert!(curses.try_color().is_some());
+        return;
+    }
+
+    let run = common::run_in_pty("build_applies_options");
+    assert!(run.status.success(), "{}", run.output);
+    assert_eq!(run.modes_after, run.modes_before);
+}
+
+#[test]
+fn build_ends_curses_on_failure() {
+    if common::is_child() {
+        // vt100 cannot hide the cursor.
+        env::set_var("TERM", "vt100");
+        let error = CursesBuilder::new()
+            .echo_input(false)
+            .input_buffering_mode(InputBufferingMode::UnbufferedNoSignals)
+            .cursor_visibility(CursorVisibility::Invisible)
+            .build()
+            .err()
+            .expect("hiding the cursor should fail");
+        assert_eq!(error.function(), "curs_set");
+        assert!(common::is_shell_mode(&stdin()));
+        // Curses can be started again.
+        initscr().unwrap();
+        return;
+    }
+
+    let run = common::run_in_pty("build_ends_curses_on_failure");
+    assert!(run.status.success(), "{}", run.output);
+    assert_eq!(run.modes_after, run.modes_before);
+}
@@ -0,0 +1,100 @@
+//! Check reading the contents of a `Window` back.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn read_back_window_contents() {
+    if common::is_child() {
+        let curses = initscr().unwrap();
+        let mut window = curses.create_window((0, 0), (3, 10)).unwrap();
+        window.move_put_str((1, 2), "he").unwrap();
+        window.turn_on_attributes(A_BOLD).unwrap();
+        window.put_str("llo").unwrap();
+        window.move_to((2, 1)).unwrap();
+
+        assert_eq!(
+            window.cell_at((1, 4)),
+            Ok(Cell {
+                character: 'l',
+                attributes: A_BOLD,
+                color_pair: ColorPairId::default(),
+            })
+        );
+        assert_eq!(window.cell_at((1, 2)).unwrap().attributes, 0);
+        let word: String = window
+            .cells((1, 2), (1, 5))
+            .unwrap()
+            .map(|(_, cell)| cell.character)
+            .collect();
+        assert_eq!(word, "hello");
+        assert_eq!(window.line_text(1).unwrap(), "  hello   ");
+        assert_eq!(window.text(), ["          ", "  hello   ", "          "]);
+        // Reading doesn't move the cursor.
+        assert_eq!(window.point(), Point { y: 2, x: 1 });
+
+        let error = window.cell_at((3, 0)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::OutOfBounds);
+        let error = window.cells((2, 8), (1, 3)).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::OutOfBounds);
+        return;
+    }
+
+    let run = common::run_in_pty("read_back_window_contents");
+    assert!(run.status.success(), "{}", run.output);
+}
+
+#[test]
+fn read_from_several_threads() {
+    if common::is_child() {
+        let curses = initscr().unwrap();
+        let mut window = curses.create_window((0, 0), (3, 10)).unwrap();
+        window.move_put_str((1, 2), "hello").unwrap();
+        window.move_to((2, 1)).unwrap();
+        let window = Arc::new(window);
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let window = window.clone();
+                thread::spawn(move || {
+                    for _ in 0..5000 {
+                        assert_eq!(window.line_text(1).unwrap(), "  hello   ");
+                        assert_eq!(window.point(), Point { y: 2, x: 1 });
+                    }
+                })
+            })
+            .collect();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(window.point(), Point { y: 2, x: 1 });
+        return;
+    }
+
+    let run = common::run_in_pty("read_from_several_threads");
+    assert!(run.status.success(), "{}", run.output);
+}
+
+#[cfg(feature = "wide")]
+#[test]
+fn read_back_wide_characters() {
+    if common::is_child() {
+        std::env::set_var("LC_ALL", "C.UTF-8");
+        let curses = CursesBuilder::new().build().unwrap();
+        let mut window = curses.create_window((0, 0), (1, 6)).unwrap();
+        window.move_put_str((0, 0), "é中x").unwrap();
+        assert_eq!(window.cell_at((0, 0)).unwrap().character, 'é');
+        assert_eq!(window.cell_at((0, 1)).unwrap().character, '中');
+        assert_eq!(window.line_text(0).unwrap(), "é中中x  ");
+        return;
+    }
+
+    let run = common::run_in_pty("read_back_wide_characters");
+    assert!(run.status.success(), "{}", run.output);
+}
@@ -0,0 +1,48 @@
+//! Check starting colors and allocating color pairs.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+
+#[test]
+fn set_color_pair_keeps_allocated_pairs() {
+    if common::is_child() {
+        let mut curses = initscr().unwrap();
+        let color = curses.start_color().unwrap();
+        let red = color.pairs_mut().allocate(COLOR_RED, COLOR_BLACK).unwrap();
+        let five = color.set_color_pair(5, COLOR_RED, COLOR_BLACK).unwrap();
+
+        // The first pair is still allocated with its colors.
+        assert_eq!(color.pairs().colors(red), Some((COLOR_RED, COLOR_BLACK)));
+        let blue = color.pairs_mut().allocate(COLOR_BLUE, COLOR_BLACK).unwrap();
+        assert_ne!(blue, red);
+        assert_eq!(color.pairs().colors(red), Some((COLOR_RED, COLOR_BLACK)));
+        color.pairs_mut().free(red).unwrap();
+        color.pairs_mut().free(five).unwrap();
+        assert_eq!(color.pairs().colors(red), None);
+        return;
+    }
+
+    let run = common::run_in_pty("set_color_pair_keeps_allocated_pairs");
+    assert!(run.status.success(), "{}", run.output);
+}
+
+#[test]
+fn color_not_started() {
+    if common::is_child() {
+        let mut curses = initscr().unwrap();
+        let error = curses.require_color().err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::ColorNotStarted);
+        assert!(curses.require_color_mut().is_err());
+        curses.start_color().unwrap();
+        assert!(curses.require_color().is_ok());
+        return;
+    }
+
+    let run = common::run_in_pty("color_not_started");
+    assert!(run.status.success(), "{}", run.output);
+}
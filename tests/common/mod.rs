@@ -0,0 +1,303 @@
+//! Run tests in a child process attached to a pseudo terminal.
+//!
+//! A test calls [`run_in_pty`] with its own name.  This runs the test
+//! binary again with [`CHILD`] set in the environment and only that test
+//! selected.  The test checks [`is_child`] to decide which side it is
+//! on.
+//!
+//! A [`Session`] runs a test or program in a pseudo terminal while the
+//! test sends it input and checks what it shows on a [`Terminal`].
+
+#![allow(dead_code)]
+
+extern crate libc;
+
+mod terminal;
+pub use self::terminal::*;
+
+use std::env;
+use std::ffi::CStr;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+pub const CHILD: &str = "PANCURSES_RESULT_PTY_CHILD";
+
+/// Test if this process is the child started by `run_in_pty`.
+pub fn is_child() -> bool {
+    env::var_os(CHILD).is_some()
+}
+
+/// The modes of a terminal: the input, output, and local flags.
+pub type Modes = (libc::tcflag_t, libc::tcflag_t, libc::tcflag_t);
+
+/// Get the modes of the terminal `tty`.
+pub fn modes<T: AsRawFd>(tty: &T) -> Modes {
+    unsafe {
+        let mut termios = std::mem::zeroed::<libc::termios>();
+        assert_eq!(libc::tcgetattr(tty.as_raw_fd(), &mut termios), 0);
+        (termios.c_iflag, termios.c_oflag, termios.c_lflag)
+    }
+}
+
+/// Test if the terminal `tty` is in the mode a shell expects: reading
+/// lines and echoing input.
+pub fn is_shell_mode<T: AsRawFd>(tty: &T) -> bool {
+    let (_, _, local) = modes(tty);
+    local & libc::ICANON != 0 && local & libc::ECHO != 0
+}
+
+/// A pseudo terminal.
+pub struct Pty {
+    pub master: File,
+    pub slave: File,
+}
+
+impl Pty {
+    pub fn open() -> Pty {
+        unsafe {
+            let master = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            assert!(master >= 0);
+            assert_eq!(libc::grantpt(master), 0);
+            assert_eq!(libc::unlockpt(master), 0);
+            let name = CStr::from_ptr(libc::ptsname(master)).to_owned();
+            let slave = libc::open(name.as_ptr(), libc::O_RDWR | libc::O_NOCTTY);
+            assert!(slave >= 0);
+            Pty {
+                master: File::from_raw_fd(master),
+                slave: File::from_raw_fd(slave),
+            }
+        }
+    }
+
+    /// Start the test `test` in a child process attached to this `Pty`.
+    ///
+    /// The child is put in its own process group so that job control
+    /// signals sent by it don't reach the test harness.
+    pub fn spawn(&self, test: &str) -> Child {
+        let mut command = Command::new(env::current_exe().unwrap());
+        command
+            .args(["--exact", test, "--nocapture", "--test-threads=1"])
+            .env(CHILD, "1");
+        self.spawn_command(command)
+    }
+
+    /// Start `command` in a child process attached to this `Pty`.
+    pub fn spawn_command(&self, mut command: Command) -> Child {
+        let stdio = || Stdio::from(self.slave.try_clone().unwrap());
+        command
+            .env("TERM", "xterm")
+            .process_group(0)
+            .stdin(stdio())
+            .stdout(stdio())
+            .stderr(stdio())
+            .spawn()
+            .unwrap()
+    }
+
+    /// Read from the `Pty` until `text` has been written to it.
+    pub fn wait_for(&mut self, text: &str) {
+        let mut output = Vec::new();
+        let mut buffer = [0; 1024];
+        while !String::from_utf8_lossy(&output).contains(text) {
+            let read = self.master.read(&mut buffer).unwrap();
+            assert!(read > 0, "{:?} was not written", text);
+            output.extend_from_slice(&buffer[..read]);
+        }
+    }
+
+    /// Set the size of the terminal.
+    pub fn set_size(&self, rows: u16, columns: u16) {
+        let size = libc::winsize {
+            ws_row: rows,
+            ws_col: columns,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        unsafe {
+            assert_eq!(
+                libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ, &size),
+                0
+            );
+        }
+    }
+
+    /// Read everything written to the `Pty`.  All processes using the
+    /// `Pty` must have exited.
+    pub fn output(self) -> String {
+        let Pty { mut master, slave } = self;
+        drop(slave);
+        let mut output = Vec::new();
+        // Reading fails with `EIO` once everything has been read.
+        let _ = master.read_to_end(&mut output);
+        String::from_utf8_lossy(&output).into_owned()
+    }
+}
+
+/// The result of `run_in_pty`.
+pub struct Run {
+    pub status: ExitStatus,
+    /// The modes of the terminal before the child started.
+    pub modes_before: Modes,
+    /// The modes of the terminal after the child exited.
+    pub modes_after: Modes,
+    pub output: String,
+}
+
+/// Run the test `test` in a child process attached to a new pseudo
+/// terminal and wait for it to exit.
+pub fn run_in_pty(test: &str) -> Run {
+    let pty = Pty::open();
+    let modes_before = modes(&pty.slave);
+    let status = pty.spawn(test).wait().unwrap();
+    let modes_after = modes(&pty.slave);
+    Run {
+        status,
+        modes_before,
+        modes_after,
+        output: pty.output(),
+    }
+}
+
+/// How long a `Session` waits for the screen to change.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// How long output must stop for before a `Session` considers the
+/// screen up to date.
+const SETTLE: Duration = Duration::from_millis(50);
+
+/// A test or program running in a pseudo terminal whose output is shown
+/// on a [`Terminal`].
+///
+/// The child is killed if it is still running when the `Session` is
+/// dropped.
+pub struct Session {
+    pty: Pty,
+    child: Child,
+    terminal: Terminal,
+}
+
+impl Session {
+    /// Run the test `test` in a child process (see [`is_child`]) on a
+    /// terminal of `rows` and `columns`.
+    pub fn spawn(test: &str, rows: u16, columns: u16) -> Session {
+        let pty = Pty::open();
+        pty.set_size(rows, columns);
+        let child = pty.spawn(test);
+        Session::new(pty, child, rows, columns)
+    }
+
+    /// Run `command` on a terminal of `rows` and `columns`.
+    pub fn spawn_command(command: Command, rows: u16, columns: u16) -> Session {
+        let pty = Pty::open();
+        pty.set_size(rows, columns);
+        let child = pty.spawn_command(command);
+        Session::new(pty, child, rows, columns)
+    }
+
+    fn new(pty: Pty, child: Child, rows: u16, columns: u16) -> Session {
+        Session {
+            pty,
+            child,
+            terminal: Terminal::new(rows as usize, columns as usize),
+        }
+    }
+
+    /// Get the screen as of the last time output was read.
+    pub fn screen(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    /// Type `input`.  Keys are sent as the escape sequences xterm uses,
+    /// for example `"\x1bOA"` for `Up`.
+    pub fn send(&mut self, input: &str) {
+        self.pty.master.write_all(input.as_bytes()).unwrap();
+    }
+
+    /// Press and release the mouse button `button` (0 is the left
+    /// button) at `row` and `column`, as an xterm SGR mouse report.
+    pub fn click(&mut self, button: u8, row: usize, column: usize) {
+        let report = format!("\x1b[<{};{};{}", button, column + 1, row + 1);
+        self.send(&format!("{}M{}m", report, report));
+    }
+
+    /// Resize the terminal and tell the child with `SIGWINCH`.
+    pub fn resize(&mut self, rows: u16, columns: u16) {
+        self.pty.set_size(rows, columns);
+        self.terminal.resize(rows as usize, columns as usize);
+        unsafe {
+            assert_eq!(
+                libc::kill(self.child.id() as libc::pid_t, libc::SIGWINCH),
+                0
+            );
+        }
+    }
+
+    /// Read output until `done` is true of the screen, then until no
+    /// more output arrives for a moment so that a refresh isn't seen
+    /// half done.
+    ///
+    /// # Panics
+    ///
+    /// Panics, showing the screen, if `done` is still false after ten
+    /// seconds of waiting.  `what` describes what was waited for.
+    pub fn wait_until<F: Fn(&Terminal) -> bool>(&mut self, what: &str, done: F) {
+        let deadline = Instant::now() + TIMEOUT;
+        while !done(&self.terminal) {
+            let now = Instant::now();
+            if now >= deadline || !self.read_output(deadline - now) {
+                panic!(
+                    "timed out waiting for {}; the screen is:\n{}",
+                    what,
+                    self.terminal.contents()
+                );
+            }
+        }
+        while self.read_output(SETTLE) {}
+    }
+
+    /// Read output until `text` is shown on the screen.
+    pub fn wait_for_text(&mut self, text: &str) {
+        self.wait_until(&format!("{:?}", text), |screen| screen.find(text).is_some());
+    }
+
+    /// Wait for the child to exit, reading the rest of its output.
+    pub fn wait(&mut self) -> ExitStatus {
+        let status = self.child.wait().unwrap();
+        while self.read_output(Duration::from_millis(0)) {}
+        status
+    }
+
+    /// Read the output that is available within `timeout` onto the
+    /// screen.  Returns `false` if there was none.
+    fn read_output(&mut self, timeout: Duration) -> bool {
+        let mut poll = libc::pollfd {
+            fd: self.pty.master.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+        if unsafe { libc::poll(&mut poll, 1, timeout) } <= 0 {
+            return false;
+        }
+        let mut buffer = [0; 4096];
+        match self.pty.master.read(&mut buffer) {
+            Ok(read) if read > 0 => {
+                self.terminal.process(&buffer[..read]);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
@@ -0,0 +1,624 @@
+//! A VT100 (xterm subset) emulator for the output of curses programs.
+//!
+//! It understands what ncurses sends for `TERM=xterm`: cursor movement,
+//! erasing, inserting and deleting, scroll regions, SGR attributes and
+//! colors, the DEC line drawing character set, the alternate screen,
+//! and the visual bell.
+
+use std::mem;
+
+/// The attributes and colors of a [`Cell`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub blink: bool,
+    pub reverse: bool,
+    pub invisible: bool,
+    /// The foreground color, or `None` for the default color.
+    pub foreground: Option<u8>,
+    /// The background color, or `None` for the default color.
+    pub background: Option<u8>,
+}
+
+/// A position on the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub character: char,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            character: ' ',
+            style: Style::default(),
+        }
+    }
+}
+
+/// The state of the escape sequence parser.
+enum State {
+    Ground,
+    Escape,
+    /// `ESC (` or `ESC )`: the next byte selects a character set.
+    Charset(bool),
+    /// The parameters and intermediate bytes of a control sequence.
+    Csi(String),
+    /// An operating system command or device control string, which is
+    /// ignored up to the string terminator.
+    String,
+    StringEscape,
+}
+
+#[derive(Clone, Copy)]
+struct Saved {
+    cursor: (usize, usize),
+    style: Style,
+    line_drawing: bool,
+}
+
+/// A terminal screen that is updated by [`process`]ing output.
+///
+/// [`process`]: struct.Terminal.html#method.process
+pub struct Terminal {
+    rows: usize,
+    columns: usize,
+    lines: Vec<Vec<Cell>>,
+    /// The main screen while the alternate screen is shown.
+    main_lines: Option<Vec<Vec<Cell>>>,
+    /// The row and column of the cursor.
+    cursor: (usize, usize),
+    /// Set after a character is written in the last column.  The cursor
+    /// wraps before the next character is written.
+    wrap_pending: bool,
+    auto_wrap: bool,
+    insert_mode: bool,
+    cursor_visible: bool,
+    /// The first and last rows that scroll.
+    scroll_region: (usize, usize),
+    style: Style,
+    /// Whether the G0 character set selected by `ESC (` is the line
+    /// drawing set.  The G1 set is ignored.
+    line_drawing: bool,
+    saved: Option<Saved>,
+    last_character: char,
+    state: State,
+    utf8: Vec<u8>,
+    flashes: usize,
+    bells: usize,
+}
+
+impl Terminal {
+    pub fn new(rows: usize, columns: usize) -> Terminal {
+        Terminal {
+            rows,
+            columns,
+            lines: vec![vec![Cell::default(); columns]; rows],
+            main_lines: None,
+            cursor: (0, 0),
+            wrap_pending: false,
+            auto_wrap: true,
+            insert_mode: false,
+            cursor_visible: true,
+            scroll_region: (0, rows.saturating_sub(1)),
+            style: Style::default(),
+            line_drawing: false,
+            saved: None,
+            last_character: ' ',
+            state: State::Ground,
+            utf8: Vec::new(),
+            flashes: 0,
+            bells: 0,
+        }
+    }
+
+    /// Get the number of rows and columns.
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+    /// Get the row and column of the cursor.
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+    /// Get the number of times the screen was flashed.
+    pub fn flashes(&self) -> usize {
+        self.flashes
+    }
+    /// Get the number of times the bell was rung.
+    pub fn bells(&self) -> usize {
+        self.bells
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> Cell {
+        self.lines[row][column]
+    }
+    /// Get the text of the row `row`, including trailing blanks.
+    pub fn line(&self, row: usize) -> String {
+        self.lines[row].iter().map(|cell| cell.character).collect()
+    }
+    /// Get the text of every row.
+    pub fn lines(&self) -> Vec<String> {
+        (0..self.rows).map(|row| self.line(row)).collect()
+    }
+    /// Get the text of the screen, with trailing blanks removed from
+    /// each row.
+    pub fn contents(&self) -> String {
+        let lines: Vec<String> = self
+            .lines()
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        lines.join("\n")
+    }
+    /// Find the row and column where `text` is shown.
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        self.lines().iter().enumerate().find_map(|(row, line)| {
+            line.find(text)
+                .map(|index| (row, line[..index].chars().count()))
+        })
+    }
+
+    /// Change the size of the screen, keeping the top left of the
+    /// contents.
+    pub fn resize(&mut self, rows: usize, columns: usize) {
+        let resize = |lines: &mut Vec<Vec<Cell>>| {
+            lines.resize(rows, vec![Cell::default(); columns]);
+            for line in lines.iter_mut() {
+                line.resize(columns, Cell::default());
+            }
+        };
+        resize(&mut self.lines);
+        if let Some(ref mut main_lines) = self.main_lines {
+            resize(main_lines);
+        }
+        self.rows = rows;
+        self.columns = columns;
+        self.scroll_region = (0, rows.saturating_sub(1));
+        self.cursor = (
+            self.cursor.0.min(rows.saturating_sub(1)),
+            self.cursor.1.min(columns.saturating_sub(1)),
+        );
+        self.wrap_pending = false;
+    }
+
+    /// Update the screen with the output `bytes`.
+    pub fn process(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.process_byte(byte);
+        }
+    }
+
+    fn process_byte(&mut self, byte: u8) {
+        match mem::replace(&mut self.state, State::Ground) {
+            State::Ground => self.ground(byte),
+            State::Escape => self.escape(byte),
+            State::Charset(g0) => {
+                if g0 {
+                    self.line_drawing = byte == b'0';
+                }
+            }
+            State::Csi(mut parameters) => {
+                if (0x40..0x7f).contains(&byte) {
+                    self.control_sequence(&parameters, byte);
+                } else if byte == 0x1b {
+                    self.state = State::Escape;
+                } else {
+                    parameters.push(byte as char);
+                    self.state = State::Csi(parameters);
+                }
+            }
+            State::String => {
+                self.state = match byte {
+                    0x07 => State::Ground,
+                    0x1b => State::StringEscape,
+                    _ => State::String,
+                }
+            }
+            State::StringEscape => {
+                if byte != b'\\' {
+                    self.state = State::String;
+                }
+            }
+        }
+    }
+
+    fn ground(&mut self, byte: u8) {
+        if !self.utf8.is_empty() || byte >= 0x80 {
+            self.utf8.push(byte);
+            match std::str::from_utf8(&self.utf8) {
+                Ok(s) => {
+                    let c = s.chars().next().unwrap();
+                    self.utf8.clear();
+                    self.print(c);
+                }
+                Err(e) if e.error_len().is_some() => {
+                    self.utf8.clear();
+                    self.print(std::char::REPLACEMENT_CHARACTER);
+                }
+                Err(_) => {}
+            }
+            return;
+        }
+        match byte {
+            0x1b => self.state = State::Escape,
+            0x07 => self.bells += 1,
+            0x08 => {
+                self.wrap_pending = false;
+                self.cursor.1 = self.cursor.1.saturating_sub(1);
+            }
+            b'\t' => {
+                self.wrap_pending = false;
+                self.cursor.1 = ((self.cursor.1 / 8 + 1) * 8).min(self.columns - 1);
+            }
+            b'\n' | 0x0b | 0x0c => self.index(),
+            b'\r' => {
+                self.wrap_pending = false;
+                self.cursor.1 = 0;
+            }
+            0x20..=0x7e => self.print(byte as char),
+            _ => {}
+        }
+    }
+
+    fn escape(&mut self, byte: u8) {
+        match byte {
+            b'[' => self.state = State::Csi(String::new()),
+            b']' | b'P' | b'_' | b'^' => self.state = State::String,
+            b'(' => self.state = State::Charset(true),
+            b')' | b'*' | b'+' => self.state = State::Charset(false),
+            b'7' => self.save_cursor(),
+            b'8' => self.restore_cursor(),
+            b'D' => self.index(),
+            b'E' => {
+                self.cursor.1 = 0;
+                self.index();
+            }
+            b'M' => self.reverse_index(),
+            b'c' => {
+                let (flashes, bells) = (self.flashes, self.bells);
+                *self = Terminal::new(self.rows, self.columns);
+                self.flashes = flashes;
+                self.bells = bells;
+            }
+            _ => {}
+        }
+    }
+
+    fn control_sequence(&mut self, parameters: &str, command: u8) {
+        let private = parameters.starts_with('?');
+        // Sequences with other prefixes or intermediate bytes (such as
+        // `ESC [ ! p` and `ESC [ > c`) aren't used for drawing.
+        if parameters
+            .chars()
+            .any(|c| !(c.is_ascii_digit() || c == ';' || (c == '?' && private)))
+        {
+            return;
+        }
+        let numbers: Vec<usize> = parameters
+            .trim_start_matches('?')
+            .split(';')
+            .map(|n| n.parse().unwrap_or(0))
+            .collect();
+        let n = |i: usize| numbers.get(i).cloned().unwrap_or(0);
+        let count = n(0).max(1);
+        let (row, column) = self.cursor;
+        if command != b'b' {
+            self.wrap_pending = false;
+        }
+        match command {
+            b'@' => {
+                let line = &mut self.lines[row];
+                for _ in 0..count.min(self.columns - column) {
+                    line.pop();
+                    line.insert(column, Cell::default());
+                }
+            }
+            b'A' => self.cursor.0 = row.saturating_sub(count),
+            b'B' => self.cursor.0 = (row + count).min(self.rows - 1),
+            b'C' => self.cursor.1 = (column + count).min(self.columns - 1),
+            b'D' => self.cursor.1 = column.saturating_sub(count),
+            b'G' | b'`' => self.cursor.1 = (count - 1).min(self.columns - 1),
+            b'd' => self.cursor.0 = (count - 1).min(self.rows - 1),
+            b'H' | b'f' => {
+                self.cursor = (
+                    (n(0).max(1) - 1).min(self.rows - 1),
+                    (n(1).max(1) - 1).min(self.columns - 1),
+                );
+            }
+            b'J' => match n(0) {
+                0 => {
+                    self.erase(row, column, self.columns);
+                    for y in row + 1..self.rows {
+                        self.erase(y, 0, self.columns);
+                    }
+                }
+                1 => {
+                    for y in 0..row {
+                        self.erase(y, 0, self.columns);
+                    }
+                    self.erase(row, 0, column + 1);
+                }
+                _ => {
+                    for y in 0..self.rows {
+                        self.erase(y, 0, self.columns);
+                    }
+                }
+            },
+            b'K' => match n(0) {
+                0 => self.erase(row, column, self.columns),
+                1 => self.erase(row, 0, column + 1),
+                _ => self.erase(row, 0, self.columns),
+            },
+            b'X' => self.erase(row, column, column + count),
+            b'L' | b'M' => {
+                let (top, bottom) = self.scroll_region;
+                if (top..=bottom).contains(&row) {
+                    for _ in 0..count.min(bottom + 1 - row) {
+                        if command == b'L' {
+                            self.scroll_down(row, bottom);
+                        } else {
+                            self.scroll_up(row, bottom);
+                        }
+                    }
+                    self.cursor.1 = 0;
+                }
+            }
+            b'P' => {
+                let blank = self.blank();
+                let line = &mut self.lines[row];
+                for _ in 0..count.min(self.columns - column) {
+                    line.remove(column);
+                    line.push(blank);
+                }
+            }
+            b'S' | b'T' => {
+                let (top, bottom) = self.scroll_region;
+                for _ in 0..count {
+                    if command == b'S' {
+                        self.scroll_up(top, bottom);
+                    } else {
+                        self.scroll_down(top, bottom);
+                    }
+                }
+            }
+            b'b' => {
+                for _ in 0..count {
+                    let c = self.last_character;
+                    self.put(c);
+                }
+            }
+            b'h' | b'l' => self.set_modes(private, &numbers, command == b'h'),
+            b'm' => self.select_graphic_rendition(&numbers),
+            b'r' if !private => {
+                let top = n(0).max(1) - 1;
+                let bottom = if n(1) == 0 { self.rows } else { n(1) };
+                if top < bottom && bottom <= self.rows {
+                    self.scroll_region = (top, bottom - 1);
+                    self.cursor = (0, 0);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn set_modes(&mut self, private: bool, modes: &[usize], set: bool) {
+        for &mode in modes {
+            match (private, mode) {
+                (false, 4) => self.insert_mode = set,
+                // The screen is shown in reverse video while flashing.
+                (true, 5) if set => self.flashes += 1,
+                (true, 7) => self.auto_wrap = set,
+                (true, 25) => self.cursor_visible = set,
+                (true, 47) | (true, 1047) | (true, 1049) => {
+                    if set && self.main_lines.is_none() {
+                        if mode == 1049 {
+                            self.save_cursor();
+                        }
+                        let blank = vec![vec![Cell::default(); self.columns]; self.rows];
+                        self.main_lines = Some(mem::replace(&mut self.lines, blank));
+                    } else if !set {
+                        if let Some(main_lines) = self.main_lines.take() {
+                            self.lines = main_lines;
+                            if mode == 1049 {
+                                self.restore_cursor();
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, numbers: &[usize]) {
+        let mut i = 0;
+        while i < numbers.len() {
+            match numbers[i] {
+                0 => self.style = Style::default(),
+                1 => self.style.bold = true,
+                2 => self.style.dim = true,
+                3 => self.style.italic = true,
+                4 => self.style.underline = true,
+                5 => self.style.blink = true,
+                7 => self.style.reverse = true,
+                8 => self.style.invisible = true,
+                22 => {
+                    self.style.bold = false;
+                    self.style.dim = false;
+                }
+                23 => self.style.italic = false,
+                24 => self.style.underline = false,
+                25 => self.style.blink = false,
+                27 => self.style.reverse = false,
+                28 => self.style.invisible = false,
+                n @ 30..=37 => self.style.foreground = Some((n - 30) as u8),
+                39 => self.style.foreground = None,
+                n @ 40..=47 => self.style.background = Some((n - 40) as u8),
+                49 => self.style.background = None,
+                n @ 90..=97 => self.style.foreground = Some((n - 90 + 8) as u8),
+                n @ 100..=107 => self.style.background = Some((n - 100 + 8) as u8),
+                n @ 38 | n @ 48 => {
+                    let color = match numbers.get(i + 1) {
+                        Some(5) => {
+                            i += 2;
+                            numbers.get(i).map(|&c| c as u8)
+                        }
+                        Some(2) => {
+                            i += 4;
+                            None
+                        }
+                        _ => None,
+                    };
+                    if n == 38 {
+                        self.style.foreground = color;
+                    } else {
+                        self.style.background = color;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// A blank cell, which has the current background color.
+    fn blank(&self) -> Cell {
+        Cell {
+            character: ' ',
+            style: Style {
+                background: self.style.background,
+                ..Style::default()
+            },
+        }
+    }
+
+    /// Blank the columns `start..end` of `row`.
+    fn erase(&mut self, row: usize, start: usize, end: usize) {
+        let blank = self.blank();
+        let end = end.min(self.columns);
+        for cell in &mut self.lines[row][start.min(end)..end] {
+            *cell = blank;
+        }
+    }
+
+    fn scroll_up(&mut self, top: usize, bottom: usize) {
+        let blank = vec![self.blank(); self.columns];
+        self.lines.remove(top);
+        self.lines.insert(bottom, blank);
+    }
+
+    fn scroll_down(&mut self, top: usize, bottom: usize) {
+        let blank = vec![self.blank(); self.columns];
+        self.lines.remove(bottom);
+        self.lines.insert(top, blank);
+    }
+
+    fn index(&mut self) {
+        self.wrap_pending = false;
+        let (top, bottom) = self.scroll_region;
+        if self.cursor.0 == bottom {
+            self.scroll_up(top, bottom);
+        } else if self.cursor.0 + 1 < self.rows {
+            self.cursor.0 += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        self.wrap_pending = false;
+        let (top, bottom) = self.scroll_region;
+        if self.cursor.0 == top {
+            self.scroll_down(top, bottom);
+        } else if self.cursor.0 > 0 {
+            self.cursor.0 -= 1;
+        }
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved = Some(Saved {
+            cursor: self.cursor,
+            style: self.style,
+            line_drawing: self.line_drawing,
+        });
+    }
+
+    fn restore_cursor(&mut self) {
+        if let Some(saved) = self.saved {
+            self.cursor = saved.cursor;
+            self.style = saved.style;
+            self.line_drawing = saved.line_drawing;
+            self.wrap_pending = false;
+        }
+    }
+
+    /// Print `c`, translating it if the line drawing set is selected.
+    fn print(&mut self, c: char) {
+        let c = if self.line_drawing {
+            line_drawing(c)
+        } else {
+            c
+        };
+        self.put(c);
+    }
+
+    fn put(&mut self, c: char) {
+        if self.wrap_pending && self.auto_wrap {
+            self.cursor.1 = 0;
+            self.index();
+        }
+        self.wrap_pending = false;
+        let (row, column) = self.cursor;
+        if self.insert_mode {
+            let line = &mut self.lines[row];
+            line.pop();
+            line.insert(column, Cell::default());
+        }
+        self.lines[row][column] = Cell {
+            character: c,
+            style: self.style,
+        };
+        self.last_character = c;
+        if column + 1 < self.columns {
+            self.cursor.1 += 1;
+        } else {
+            self.wrap_pending = true;
+        }
+    }
+}
+
+/// Translate a character in the DEC special graphics set.
+fn line_drawing(c: char) -> char {
+    match c {
+        '`' => '◆',
+        'a' => '▒',
+        'f' => '°',
+        'g' => '±',
+        'j' => '┘',
+        'k' => '┐',
+        'l' => '┌',
+        'm' => '└',
+        'n' => '┼',
+        'o' => '⎺',
+        'p' => '⎻',
+        'q' => '─',
+        'r' => '⎼',
+        's' => '⎽',
+        't' => '├',
+        'u' => '┤',
+        'v' => '┴',
+        'w' => '┬',
+        'x' => '│',
+        'y' => '≤',
+        'z' => '≥',
+        '{' => 'π',
+        '|' => '≠',
+        '}' => '£',
+        '~' => '·',
+        c => c,
+    }
+}
@@ -0,0 +1,36 @@
+//! Check that the panic hook returns the terminal to shell mode.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+
+const MESSAGE: &str = "the panic message";
+
+#[test]
+fn panic_hook_restores_terminal() {
+    if common::is_child() {
+        let mut curses = initscr().unwrap();
+        curses.install_panic_hook();
+        curses
+            .set_input_buffering_mode(InputBufferingMode::UnbufferedNoSignals)
+            .unwrap();
+        curses.set_echo_input(false).unwrap();
+        panic!("{}", MESSAGE);
+    }
+
+    let run = common::run_in_pty("panic_hook_restores_terminal");
+    assert!(!run.status.success());
+    assert_eq!(run.modes_after, run.modes_before);
+
+    let message = run.output.find(MESSAGE).expect("panic message not printed");
+    // xterm leaves the alternate screen with `rmcup` in `endwin`.
+    let end = run
+        .output
+        .rfind("\x1b[?1049l")
+        .expect("curses was not ended");
+    assert!(end < message, "{:?}", run.output);
+}
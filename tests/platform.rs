@@ -0,0 +1,22 @@
+//! Check that options only some platforms support do nothing elsewhere.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+
+#[test]
+fn windows_only_options_do_nothing() {
+    if common::is_child() {
+        let mut curses = initscr().unwrap();
+        assert_eq!(curses.set_force_blink(true), Ok(()));
+        assert_eq!(curses.set_title("title"), Ok(()));
+        return;
+    }
+
+    let run = common::run_in_pty("windows_only_options_do_nothing");
+    assert!(run.status.success(), "{}", run.output);
+}
@@ -0,0 +1,76 @@
+//! Check that resizing the terminal resizes `stdscr` and that
+//! `Curses::relayout` lays out the windows registered with
+//! `Curses::relayout_on_resize`.
+
+#![cfg(unix)]
+
+extern crate libc;
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+use std::io::Write;
+
+#[test]
+fn resize_relayouts_windows() {
+    if common::is_child() {
+        let mut curses = initscr().unwrap();
+        curses
+            .set_input_buffering_mode(InputBufferingMode::UnbufferedNoSignals)
+            .unwrap();
+        curses.set_echo_input(false).unwrap();
+        let mut status = curses.create_window((0, 0), (1, 1)).unwrap();
+        curses.relayout_on_resize(&status, |size| {
+            ((size.rows - 1, 0).into(), (1, size.columns).into())
+        });
+        curses.window_mut().put_str("ready").unwrap();
+        curses.window_mut().refresh().unwrap();
+
+        // `SIGWINCH` may be handled on another thread, in which case the
+        // resize is only noticed by the next refresh.
+        let resize = loop {
+            match curses.read_event().unwrap() {
+                Some(Event::Key(_)) => curses.window_mut().refresh().unwrap(),
+                event => break event,
+            }
+        };
+        assert_eq!(
+            resize,
+            Some(Event::Resize {
+                rows: 30,
+                columns: 100
+            })
+        );
+        assert_eq!(curses.window().size(), Dimension::from((30, 100)));
+        assert_eq!(
+            curses.layout_of(&status),
+            Some(((29, 0).into(), (1, 100).into()))
+        );
+        curses.relayout(&mut [&mut status]).unwrap();
+        assert_eq!(status.beginning(), Point::from((29, 0)));
+        assert_eq!(status.size(), Dimension::from((1, 100)));
+
+        // Windows without a layout are left alone.
+        let mut other = curses.create_window((1, 1), (2, 2)).unwrap();
+        assert_eq!(curses.layout_of(&other), None);
+        curses.relayout(&mut [&mut other]).unwrap();
+        assert_eq!(other.size(), Dimension::from((2, 2)));
+        // Nor does the layout of `status` apply to its copies.
+        let copy = status.clone();
+        assert_eq!(curses.layout_of(&copy), None);
+        return;
+    }
+
+    let mut pty = common::Pty::open();
+    pty.set_size(24, 80);
+    let mut child = pty.spawn("resize_relayouts_windows");
+    pty.wait_for("ready");
+    pty.set_size(30, 100);
+    unsafe {
+        assert_eq!(libc::kill(child.id() as libc::pid_t, libc::SIGWINCH), 0);
+    }
+    pty.master.write_all(b"x").unwrap();
+    let status = child.wait().unwrap();
+    assert!(status.success(), "{}", pty.output());
+}
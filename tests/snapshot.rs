@@ -0,0 +1,25 @@
+//! Check comparing the contents of a `Window` against a stored snapshot.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+
+#[test]
+fn window_matches_snapshot() {
+    if common::is_child() {
+        let curses = initscr().unwrap();
+        let mut window = curses.create_window((0, 0), (3, 12)).unwrap();
+        window.move_put_str((1, 1), "status:").unwrap();
+        window.turn_on_attributes(A_BOLD).unwrap();
+        window.put_str(" ok").unwrap();
+        assert_snapshot(&window, "tests/snapshots/window.txt");
+        return;
+    }
+
+    let run = common::run_in_pty("window_matches_snapshot");
+    assert!(run.status.success(), "{}", run.output);
+}
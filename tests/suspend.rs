@@ -0,0 +1,60 @@
+//! Check that `Curses::suspend` and `Curses::background` switch between
+//! shell mode and program mode.
+
+#![cfg(unix)]
+
+extern crate libc;
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+use std::io::stdin;
+
+fn start() -> Curses {
+    let mut curses = initscr().unwrap();
+    curses
+        .set_input_buffering_mode(InputBufferingMode::UnbufferedNoSignals)
+        .unwrap();
+    curses.set_echo_input(false).unwrap();
+    curses
+}
+
+#[test]
+fn suspend_restores_shell_mode() {
+    if common::is_child() {
+        let mut curses = start();
+        assert!(!common::is_shell_mode(&stdin()));
+        let in_shell_mode = curses.suspend(|| common::is_shell_mode(&stdin()));
+        assert_eq!(in_shell_mode, Ok(true));
+        assert!(!common::is_shell_mode(&stdin()));
+        return;
+    }
+
+    let run = common::run_in_pty("suspend_restores_shell_mode");
+    assert!(run.status.success(), "{}", run.output);
+    assert_eq!(run.modes_after, run.modes_before);
+}
+
+#[test]
+fn background_stops_process() {
+    if common::is_child() {
+        let mut curses = start();
+        curses.background().unwrap();
+        assert!(!common::is_shell_mode(&stdin()));
+        return;
+    }
+
+    let pty = common::Pty::open();
+    let mut child = pty.spawn("background_stops_process");
+    let pid = child.id() as libc::pid_t;
+    unsafe {
+        let mut status = 0;
+        assert_eq!(libc::waitpid(pid, &mut status, libc::WUNTRACED), pid);
+        assert!(libc::WIFSTOPPED(status));
+        assert!(common::is_shell_mode(&pty.slave));
+        assert_eq!(libc::kill(pid, libc::SIGCONT), 0);
+    }
+    let status = child.wait().unwrap();
+    assert!(status.success(), "{}", pty.output());
+}
@@ -0,0 +1,600 @@
+//! Check what curses programs show on a terminal, end to end, by running
+//! them in a `common::Session`.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use common::Session;
+use pancurses_result::*;
+use std::time::Duration;
+
+fn start() -> Curses {
+    CursesBuilder::new()
+        .echo_input(false)
+        .input_buffering_mode(InputBufferingMode::UnbufferedNoSignals)
+        .read_interpolate_function_keys(true)
+        .build()
+        .unwrap()
+}
+
+/// Show `ready` and wait for `q` so the screen can be checked before
+/// curses ends.
+fn finish(mut curses: Curses) {
+    curses.window_mut().move_put_str((0, 0), "ready").unwrap();
+    curses.window_mut().refresh().unwrap();
+    while curses.window_mut().read_char() != Some(Input::Character('q')) {}
+    curses.end_curses().unwrap();
+}
+
+/// Quit the child and check that it succeeded.
+fn quit(mut session: Session) {
+    session.send("q");
+    let status = session.wait();
+    assert!(status.success(), "{}", session.screen().contents());
+}
+
+#[test]
+fn border_and_color() {
+    if common::is_child() {
+        let mut curses = start();
+        let pair = curses
+            .start_color()
+            .unwrap()
+            .pairs_mut()
+            .allocate(COLOR_RED, COLOR_BLUE)
+            .unwrap();
+        curses.window_mut().refresh().unwrap();
+        let mut window = curses.create_window((2, 3), (3, 7)).unwrap();
+        window.draw_box(0u32, 0u32).unwrap();
+        window.set_color(pair).unwrap();
+        window.turn_on_attributes(A_BOLD).unwrap();
+        window.move_put_str((1, 1), "hot").unwrap();
+        window.refresh().unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("border_and_color", 10, 20);
+    session.wait_for_text("ready");
+    let screen = session.screen();
+    assert_eq!(screen.line(2), "   ┌─────┐          ");
+    assert_eq!(screen.line(3), "   │hot  │          ");
+    assert_eq!(screen.line(4), "   └─────┘          ");
+    let style = screen.cell(3, 4).style;
+    assert!(style.bold);
+    assert_eq!(style.foreground, Some(COLOR_RED as u8));
+    assert_eq!(style.background, Some(COLOR_BLUE as u8));
+    assert!(!screen.cell(3, 3).style.bold);
+    quit(session);
+}
+
+#[test]
+fn scrolling() {
+    if common::is_child() {
+        let mut curses = start();
+        curses.window_mut().refresh().unwrap();
+        let mut window = curses.create_window((1, 0), (3, 10)).unwrap();
+        window.set_scroll_enabled(true).unwrap();
+        for line in 1..=5 {
+            window.put_str(format!("line {}\n", line)).unwrap();
+        }
+        window.refresh().unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("scrolling", 5, 10);
+    session.wait_for_text("ready");
+    assert_eq!(
+        session.screen().lines(),
+        [
+            "ready     ",
+            "line 4    ",
+            "line 5    ",
+            "          ",
+            "          "
+        ]
+    );
+    quit(session);
+}
+
+#[test]
+fn flash() {
+    if common::is_child() {
+        let mut curses = start();
+        curses.flash().unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("flash", 5, 10);
+    session.wait_for_text("ready");
+    assert_eq!(session.screen().flashes(), 1);
+    quit(session);
+}
+
+#[test]
+fn resize_terminal() {
+    if common::is_child() {
+        let mut curses = start();
+        curses.resize_terminal(4, 8).unwrap();
+        curses.window_mut().draw_box(0u32, 0u32).unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("resize_terminal", 6, 10);
+    session.wait_for_text("ready");
+    assert_eq!(
+        session.screen().lines(),
+        [
+            "ready──┐  ",
+            "│      │  ",
+            "│      │  ",
+            "└──────┘  ",
+            "          ",
+            "          ",
+        ]
+    );
+    quit(session);
+}
+
+#[test]
+fn keys_and_mouse() {
+    if common::is_child() {
+        let mut curses = start();
+        curses.set_escape_delay(Duration::from_millis(25)).unwrap();
+        curses.set_mouse_mask(ALL_MOUSE_EVENTS, None).unwrap();
+        curses.window_mut().put_str("ready").unwrap();
+        curses.window_mut().refresh().unwrap();
+        for row in 1..4 {
+            let text = match curses.read_event().unwrap() {
+                Some(Event::Key(key)) => key.to_string(),
+                Some(Event::Mouse(mouse)) => format!("{:?} {:?}", mouse.action, mouse.point),
+                event => format!("{:?}", event),
+            };
+            curses.window_mut().move_put_str((row, 0), text).unwrap();
+            curses.window_mut().refresh().unwrap();
+        }
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("keys_and_mouse", 6, 40);
+    session.wait_for_text("ready");
+    session.send("\x1bOA");
+    session.wait_for_text("Up");
+    session.send("\x18");
+    session.wait_for_text("C-x");
+    session.click(0, 4, 7);
+    session.wait_until("the click", |screen| screen.line(3).trim_end() != "");
+    assert_eq!(
+        session.screen().line(3).trim_end(),
+        "Click(Left) Point { y: 4, x: 7 }"
+    );
+    assert_eq!(session.screen().cursor(), (0, 5));
+    quit(session);
+}
+
+#[test]
+fn read_and_unread_chars() {
+    if common::is_child() {
+        let mut curses = start();
+        curses.window_mut().put_str("ready").unwrap();
+        curses.window_mut().refresh().unwrap();
+        let mut inputs = Vec::new();
+        for _ in 0..2 {
+            let input = curses.window_mut().read_char().unwrap();
+            curses.window_mut().unread_char(&input).unwrap();
+            inputs.push(curses.window_mut().read_char().unwrap());
+        }
+        // Escape characters outside of ASCII, which can't be drawn in
+        // every build.
+        let text: String = format!("{:?}", inputs)
+            .chars()
+            .map(|c| match c {
+                ' '..='~' => c.to_string(),
+                _ => c.escape_unicode().to_string(),
+            })
+            .collect();
+        curses.window_mut().move_put_str((1, 0), text).unwrap();
+        curses.window_mut().refresh().unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("read_and_unread_chars", 4, 40);
+    session.wait_for_text("ready");
+    session.send("\u{e9}\x1bOB");
+    session.wait_until("the inputs", |screen| screen.line(1).trim_end() != "");
+    assert_eq!(
+        session.screen().line(1).trim_end(),
+        "[Character('\\u{e9}'), KeyDown]"
+    );
+    quit(session);
+}
+
+#[test]
+fn incomplete_character_times_out() {
+    if common::is_child() {
+        let mut curses = start();
+        let window = curses.window_mut();
+        window.set_timeout(Some(Duration::from_millis(50)));
+        // The first byte of `é` without the rest.
+        window.unread_char(&Input::Unknown(0xc3)).unwrap();
+        let text = format!("{:?}", window.read_char());
+        window.set_timeout(None);
+        window.move_put_str((1, 0), text).unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("incomplete_character_times_out", 4, 20);
+    session.wait_for_text("ready");
+    assert_eq!(session.screen().line(1).trim_end(), "None");
+    quit(session);
+}
+
+#[test]
+fn prefix_on_nodelay_window() {
+    if common::is_child() {
+        let mut curses = start();
+        let mut keymap = Keymap::new();
+        keymap.bind("C-x C-f", "find").unwrap();
+        let mut dispatcher = Dispatcher::new(&keymap);
+        let window = curses.window_mut();
+        window.set_block_on_read(false).unwrap();
+        window.put_str("ready").unwrap();
+        window.refresh().unwrap();
+        loop {
+            match dispatcher.read(window) {
+                Some(Dispatch::Pending) => break,
+                Some(_) => panic!("expected a prefix"),
+                None => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        window.move_put_str((1, 0), "pending").unwrap();
+        window.refresh().unwrap();
+        // The pending prefix waits for the next key without a chord
+        // timeout, even though the window doesn't block.
+        let dispatch = dispatcher.read(window);
+        let text = match dispatch {
+            Some(Dispatch::Action(action)) => action.to_string(),
+            _ => "no action".to_string(),
+        };
+        window.set_block_on_read(true).unwrap();
+        window.move_put_str((2, 0), text).unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("prefix_on_nodelay_window", 4, 20);
+    session.wait_for_text("ready");
+    session.send("\x18");
+    session.wait_for_text("pending");
+    session.send("\x06");
+    session.wait_until("the action", |screen| screen.line(2).trim_end() != "");
+    assert_eq!(session.screen().line(2).trim_end(), "find");
+    quit(session);
+}
+
+#[test]
+fn line_editor_on_last_row() {
+    if common::is_child() {
+        let mut curses = start();
+        let size = curses.window().size();
+        curses.window_mut().put_str("ready").unwrap();
+        // Reads block while editing, even if the window doesn't.
+        curses.window_mut().set_block_on_read(false).unwrap();
+        let mut editor = LineEditor::new();
+        let line = editor
+            .read_line(curses.window_mut(), (size.rows - 1, 0), size.columns)
+            .unwrap();
+        assert_eq!(curses.window_mut().read_char(), None);
+        curses.window_mut().set_block_on_read(true).unwrap();
+        let text = line.unwrap().replace("efghijk", "..");
+        curses.window_mut().move_put_str((1, 0), text).unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("line_editor_on_last_row", 4, 10);
+    session.wait_for_text("ready");
+    session.send("abcdefghijkl");
+    session.wait_for_text("efghijkl");
+    // The bottom right corner is left blank.
+    assert_eq!(session.screen().line(3), "efghijkl  ");
+    assert_eq!(session.screen().cursor(), (3, 8));
+    session.send("\r");
+    session.wait_for_text("abcd..l");
+    quit(session);
+}
+
+#[test]
+fn rects() {
+    if common::is_child() {
+        let mut curses = start();
+        let rect = Rect::new((1, 2), (3, 6));
+        let window = curses.window_mut();
+        window
+            .draw_border_rect(rect, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32)
+            .unwrap();
+        assert_eq!(window.point(), Point { y: 0, x: 0 });
+        let mut inner = window.create_sub_window_rect(rect.inset(1, 1)).unwrap();
+        assert_eq!(inner.bounds(), Rect::new((2, 3), (1, 4)));
+        inner.put_str("in").unwrap();
+        inner.sync_up();
+        let line = Rect::new((4, 0), (1, 5));
+        let error = window
+            .draw_border_rect(line, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32)
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("rects", 5, 10);
+    session.wait_for_text("ready");
+    assert_eq!(
+        session.screen().lines(),
+        [
+            "ready     ",
+            "  ┌────┐  ",
+            "  │in  │  ",
+            "  └────┘  ",
+            "          "
+        ]
+    );
+    quit(session);
+}
+
+#[test]
+fn layout_follows_resize() {
+    if common::is_child() {
+        let mut curses = start();
+        let mut main = curses.create_window((0, 0), (1, 1)).unwrap();
+        let mut status = curses.create_window((0, 0), (1, 1)).unwrap();
+        let layout = Layout::vertical(vec![Constraint::Fill(1), Constraint::Length(1)]);
+        layout
+            .relayout_on_resize(&mut curses, &mut [&mut main, &mut status])
+            .unwrap();
+        // `SIGWINCH` may be handled on another thread, in which case the
+        // resize is only noticed when the next key is read, so don't
+        // wait for keys indefinitely.
+        curses
+            .window_mut()
+            .set_timeout(Some(Duration::from_millis(50)));
+        loop {
+            curses.window_mut().refresh().unwrap();
+            main.erase().unwrap();
+            main.draw_box(0u32, 0u32).unwrap();
+            main.refresh().unwrap();
+            status.erase().unwrap();
+            let bounds = status.bounds();
+            let text = format!("at {} of {}", bounds.origin.y, bounds.size.columns);
+            status.put_str(text).unwrap();
+            status.refresh().unwrap();
+            let quit = KeyEvent::from(Key::Char('q'));
+            match curses.read_event().unwrap() {
+                Some(Event::Key(key)) if key == quit => break,
+                Some(Event::Resize { .. }) => {
+                    curses.relayout(&mut [&mut main, &mut status]).unwrap()
+                }
+                _ => {}
+            }
+        }
+        curses.end_curses().unwrap();
+        return;
+    }
+
+    let mut session = Session::spawn("layout_follows_resize", 4, 20);
+    session.wait_for_text("at 3 of 20");
+    assert_eq!(session.screen().line(0), "┌──────────────────┐");
+    assert_eq!(session.screen().line(2), "└──────────────────┘");
+    session.resize(6, 30);
+    session.wait_for_text("at 5 of 30");
+    assert_eq!(session.screen().line(4), format!("└{}┘", "─".repeat(28)));
+    quit(session);
+}
+
+#[test]
+fn pad_viewport() {
+    if common::is_child() {
+        let mut curses = start();
+        let mut pad = curses.create_pad((100, 10)).unwrap();
+        for row in 0..100 {
+            pad.move_put_str((row, 0), format!("line {}", row)).unwrap();
+        }
+        curses.window_mut().put_str("ready").unwrap();
+        curses.window_mut().refresh().unwrap();
+        pad.refresh_region((42, 0), Rect::new((1, 2), (2, 7)))
+            .unwrap();
+        // An empty rectangle shows nothing.
+        pad.refresh_region((0, 0), Rect::new((3, 0), (0, 7)))
+            .unwrap();
+        curses.window_mut().read_char();
+        return;
+    }
+
+    let mut session = Session::spawn("pad_viewport", 4, 12);
+    session.wait_for_text("line 43");
+    assert_eq!(
+        session.screen().lines(),
+        [
+            "ready       ",
+            "  line 42   ",
+            "  line 43   ",
+            "            "
+        ]
+    );
+    quit(session);
+}
+
+#[test]
+fn wrapped_text() {
+    if common::is_child() {
+        let mut curses = start();
+        let options = WrapOptions::new()
+            .alignment(Alignment::Right)
+            .ellipsis("...");
+        let rect = Rect::new((1, 0), (4, 10));
+        let window = curses.window_mut();
+        window.set_scroll_enabled(true).unwrap();
+        let text = "one two three four five six seven eight";
+        assert_eq!(window.put_wrapped(rect, text, &options).unwrap(), 4);
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("wrapped_text", 5, 10);
+    session.wait_for_text("ready");
+    assert_eq!(
+        session.screen().lines(),
+        [
+            "ready     ",
+            "   one two",
+            "three four",
+            "  five six",
+            "  seven..."
+        ]
+    );
+    quit(session);
+}
+
+#[cfg(feature = "wide")]
+#[test]
+fn wrapped_wide_text_in_corner() {
+    if common::is_child() {
+        std::env::set_var("LC_ALL", "C.UTF-8");
+        let mut curses = CursesBuilder::new().echo_input(false).build().unwrap();
+        let rect = Rect::new((1, 0), (2, 6));
+        let window = curses.window_mut();
+        let lines = window.put_wrapped(rect, "abc 漢字漢", &WrapOptions::new());
+        assert_eq!(lines, Ok(2));
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("wrapped_wide_text_in_corner", 3, 6);
+    session.wait_for_text("ready");
+    assert_eq!(session.screen().line(1).trim_end(), "abc");
+    assert_eq!(session.screen().line(2).trim_end(), "漢字漢");
+    quit(session);
+}
+
+#[test]
+fn styled_text() {
+    if common::is_child() {
+        let mut curses = start();
+        let pairs = curses.start_color().unwrap().pairs_mut();
+        let text = StyledText::parse("[bold red]Error:[/] file [u]a.rs[/]", pairs).unwrap();
+        let window = curses.window_mut();
+        window.move_to((1, 0)).unwrap();
+        window.turn_on_attributes(A_REVERSE).unwrap();
+        let before = window.attributes();
+        window.put_styled(&text).unwrap();
+        assert_eq!(window.attributes(), before);
+        window.turn_off_attributes(A_REVERSE).unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("styled_text", 3, 20);
+    session.wait_for_text("ready");
+    let screen = session.screen();
+    assert_eq!(screen.line(1), "Error: file a.rs    ");
+    let error = screen.cell(1, 0).style;
+    assert!(error.bold && error.reverse);
+    assert_eq!(error.foreground, Some(COLOR_RED as u8));
+    let plain = screen.cell(1, 7).style;
+    assert!(!plain.bold && !plain.underline && plain.reverse);
+    let file = screen.cell(1, 12).style;
+    assert!(file.underline && !file.bold);
+    quit(session);
+}
+
+#[test]
+fn ansi_text() {
+    if common::is_child() {
+        let mut curses = start();
+        curses.window_mut().refresh().unwrap();
+        let mut window = curses.create_window((1, 0), (1, 20)).unwrap();
+        let color = curses.start_color().unwrap();
+        window
+            .put_ansi("\x1b[1;31merror\x1b[0m: \x1b]0;t\x07ok\x1b[4m!", color)
+            .unwrap();
+        assert_eq!(window.attributes().0, 0);
+        window.refresh().unwrap();
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("ansi_text", 3, 20);
+    session.wait_for_text("ready");
+    let screen = session.screen();
+    assert_eq!(screen.line(1), "error: ok!          ");
+    let error = screen.cell(1, 0).style;
+    assert!(error.bold);
+    assert_eq!(error.foreground, Some(COLOR_RED as u8));
+    assert!(!screen.cell(1, 7).style.bold);
+    assert!(screen.cell(1, 9).style.underline);
+    quit(session);
+}
+
+fn draw_titled_border(curses: &mut Curses) {
+    curses.window_mut().refresh().unwrap();
+    let mut window = curses.create_window((1, 0), (3, 12)).unwrap();
+    let border = Border::new(BorderStyle::Double)
+        .title("Log", Alignment::Center)
+        .footer("1/2", Alignment::Right);
+    window.draw_styled_border(border).unwrap();
+    assert_eq!(window.point(), Point { y: 0, x: 0 });
+    window.refresh().unwrap();
+}
+
+#[test]
+fn styled_border() {
+    if common::is_child() {
+        std::env::set_var("LC_ALL", "C.UTF-8");
+        let mut curses = CursesBuilder::new().echo_input(false).build().unwrap();
+        draw_titled_border(&mut curses);
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("styled_border", 4, 12);
+    session.wait_for_text("ready");
+    // Without the `wide` feature, the lines of the alternate character
+    // set are drawn instead.
+    let lines = if cfg!(feature = "wide") {
+        ["╔══ Log ═══╗", "║          ║", "╚═════ 1/2 ╝"]
+    } else {
+        ["┌── Log ───┐", "│          │", "└───── 1/2 ┘"]
+    };
+    assert_eq!(session.screen().lines()[1..], lines);
+    quit(session);
+}
+
+#[test]
+fn ascii_border_fallback() {
+    if common::is_child() {
+        // Curses takes the locale from the environment, so don't
+        // depend on the one the tests are run in.
+        std::env::set_var("LC_ALL", "C");
+        let mut curses = start();
+        draw_titled_border(&mut curses);
+        finish(curses);
+        return;
+    }
+
+    let mut session = Session::spawn("ascii_border_fallback", 4, 12);
+    session.wait_for_text("ready");
+    assert_eq!(
+        session.screen().lines()[1..],
+        ["+-- Log ---+", "|          |", "+----- 1/2 +"]
+    );
+    quit(session);
+}
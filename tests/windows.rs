@@ -0,0 +1,42 @@
+//! Check creating and deleting top-level windows.
+
+#![cfg(unix)]
+
+extern crate pancurses_result;
+
+mod common;
+
+use pancurses_result::*;
+
+#[test]
+fn create_and_delete_windows() {
+    if common::is_child() {
+        let curses = initscr().unwrap();
+        let screen = curses.window().size();
+
+        let window = curses.create_window((1, 1), (2, 3)).unwrap();
+        assert_eq!(window.beginning(), Point { y: 1, x: 1 });
+        assert_eq!(
+            window.size(),
+            Dimension {
+                rows: 2,
+                columns: 3
+            }
+        );
+        window.delete_window().unwrap();
+
+        // Windows outside of the screen are refused rather than created.
+        let error = curses.create_window((-1, 0), (1, 1)).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::OutOfBounds);
+        let error = curses.create_window((0, 0), (1, -1)).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::OutOfBounds);
+
+        let pad = curses.create_pad((screen.rows * 2, 5)).unwrap();
+        let error = pad.create_sub_pad((0, 3), (1, 5)).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::OutOfBounds);
+        return;
+    }
+
+    let run = common::run_in_pty("create_and_delete_windows");
+    assert!(run.status.success(), "{}", run.output);
+}